@@ -40,16 +40,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         lines_of_code: Some(2000),
         crate_size: 75,
         releases: 5,
+        downloads: Some(12_345_678),
+        recent_downloads: Some(456_789),
+        download_history: &[120, 135, 128, 160, 172, 150, 190, 210, 205, 230],
     };
     match generator.generate(data).await {
-        Ok(temp_file) => {
+        Ok(image) => {
             let output_path = "test_og_image.png";
-            std::fs::copy(temp_file.path(), output_path)?;
+            std::fs::copy(image.path(), output_path)?;
             println!("Successfully generated image at: {output_path}");
             println!(
                 "Image file size: {} bytes",
                 std::fs::metadata(output_path)?.len()
             );
+            if let Some(optimization) = image.optimization {
+                println!(
+                    "PNG optimization: {} -> {} bytes",
+                    optimization.original_size, optimization.optimized_size
+                );
+            }
         }
         Err(error) => {
             println!("Failed to generate image: {error}");
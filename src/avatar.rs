@@ -0,0 +1,387 @@
+//! Downloading of author avatars with timeouts, size limits and retries.
+
+use crate::{OgImageError, OgImageGenerator};
+use bytes::{Bytes, BytesMut};
+use reqwest::StatusCode;
+use std::time::Duration;
+use tracing::{debug, error, warn};
+
+/// Timeouts, size limit and retries of the avatar downloads.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use crates_io_og_image::{AvatarFetchOptions, OgImageGenerator};
+///
+/// let options = AvatarFetchOptions::default()
+///     .with_timeout(Duration::from_secs(5))
+///     .with_max_size(1024 * 1024)
+///     .with_max_retries(1);
+///
+/// let generator = OgImageGenerator::default().with_avatar_fetch_options(options);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AvatarFetchOptions {
+    connect_timeout: Duration,
+    timeout: Duration,
+    max_size: u64,
+    max_retries: u32,
+    retry_backoff: Duration,
+}
+
+impl AvatarFetchOptions {
+    /// Sets the timeout for connecting to the avatar host.
+    ///
+    /// Defaults to 5 seconds.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Returns the timeout for connecting to the avatar host.
+    pub fn connect_timeout(&self) -> Duration {
+        self.connect_timeout
+    }
+
+    /// Sets the timeout of a single download attempt, from connecting to
+    /// the avatar host until the whole avatar has been read.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the timeout of a single download attempt.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Sets the maximum size of an avatar in bytes.
+    ///
+    /// The limit is enforced while the avatar is downloaded, so larger
+    /// avatars are never read into memory completely. Defaults to 5 MiB.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Returns the maximum size of an avatar in bytes.
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Sets how often a download is retried after a timeout or a server
+    /// error (5xx) response.
+    ///
+    /// Defaults to 2 retries, i.e. 3 attempts in total.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Returns how often a download is retried.
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Sets the delay before the first retry, which is doubled for every
+    /// further retry.
+    ///
+    /// Defaults to 250 milliseconds.
+    pub fn with_retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Returns the delay before the first retry.
+    pub fn retry_backoff(&self) -> Duration {
+        self.retry_backoff
+    }
+
+    /// Creates the HTTP client for the avatar downloads.
+    pub(crate) fn build_client(&self) -> Result<reqwest::Client, OgImageError> {
+        reqwest::Client::builder()
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.timeout)
+            .build()
+            .map_err(OgImageError::HttpClientError)
+    }
+}
+
+impl Default for AvatarFetchOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(5),
+            timeout: Duration::from_secs(10),
+            max_size: 5 * 1024 * 1024,
+            max_retries: 2,
+            retry_backoff: Duration::from_millis(250),
+        }
+    }
+}
+
+/// The outcome of a failed download attempt.
+enum AttemptError {
+    /// The attempt timed out, and can be retried.
+    Timeout,
+    /// The server responded with a server error, and can be retried.
+    ServerError(StatusCode),
+    /// The download failed and must not be retried.
+    Fatal(OgImageError),
+}
+
+/// Downloads an avatar and detects its image format.
+///
+/// Returns `None` if the avatar was not found or has an unsupported
+/// format, so that the avatar is skipped instead of failing the whole
+/// image generation.
+pub(crate) async fn download_avatar(
+    client: &reqwest::Client,
+    avatar: &str,
+    options: &AvatarFetchOptions,
+) -> Result<Option<(&'static str, Bytes)>, OgImageError> {
+    let mut attempts = 0;
+    let bytes = loop {
+        attempts += 1;
+
+        let error = match download_attempt(client, avatar, options).await {
+            Ok(bytes) => break bytes,
+            Err(AttemptError::Fatal(error)) => return Err(error),
+            Err(error) => error,
+        };
+
+        if attempts > options.max_retries {
+            return Err(match error {
+                AttemptError::ServerError(status) => OgImageError::AvatarServerError {
+                    url: avatar.to_string(),
+                    status,
+                    attempts,
+                },
+                _ => OgImageError::AvatarTimeout {
+                    url: avatar.to_string(),
+                    attempts,
+                },
+            });
+        }
+
+        let backoff = options
+            .retry_backoff
+            .saturating_mul(2u32.saturating_pow(attempts - 1));
+        warn!(url = %avatar, attempts, backoff_ms = backoff.as_millis(), "Retrying avatar download");
+        tokio::time::sleep(backoff).await;
+    };
+
+    let Some(bytes) = bytes else {
+        return Ok(None);
+    };
+
+    debug!(url = %avatar, size_bytes = bytes.len(), "Avatar downloaded successfully");
+
+    // Detect the image format and determine the appropriate file extension
+    let Some(extension) = OgImageGenerator::detect_image_format(&bytes) else {
+        // Format not supported, log warning with first 20 bytes for debugging
+        let debug_bytes = &bytes[..bytes.len().min(20)];
+        let hex_bytes = debug_bytes
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        warn!("Unsupported avatar format at {avatar}, first 20 bytes: {hex_bytes}");
+
+        // Skip this avatar and continue with the next one
+        return Ok(None);
+    };
+
+    Ok(Some((extension, bytes)))
+}
+
+/// Downloads an avatar once, returning `None` if it was not found.
+async fn download_attempt(
+    client: &reqwest::Client,
+    avatar: &str,
+    options: &AvatarFetchOptions,
+) -> Result<Option<Bytes>, AttemptError> {
+    let download_error = |err: reqwest::Error| {
+        if err.is_timeout() {
+            warn!(url = %avatar, "Avatar download timed out");
+            return AttemptError::Timeout;
+        }
+
+        error!(url = %avatar, error = %err, "Failed to download avatar");
+        AttemptError::Fatal(OgImageError::AvatarDownloadError {
+            url: avatar.to_string(),
+            source: err,
+        })
+    };
+
+    // Download the avatar from the URL
+    debug!(url = %avatar, "Downloading avatar from URL: {avatar}");
+    let mut response = client.get(avatar).send().await.map_err(download_error)?;
+
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        warn!(url = %avatar, "Avatar URL returned 404 Not Found");
+        return Ok(None); // Skip this avatar if not found
+    }
+
+    if status.is_server_error() {
+        warn!(url = %avatar, status = %status, "Avatar URL returned a server error");
+        return Err(AttemptError::ServerError(status));
+    }
+
+    if let Err(err) = response.error_for_status_ref() {
+        return Err(download_error(err));
+    }
+
+    let too_large = || {
+        warn!(url = %avatar, max_size = options.max_size, "Avatar exceeds the maximum size");
+        AttemptError::Fatal(OgImageError::AvatarTooLarge {
+            url: avatar.to_string(),
+            max_size: options.max_size,
+        })
+    };
+
+    let content_length = response.content_length();
+    debug!(
+        url = %avatar,
+        content_length = ?content_length,
+        status = %status,
+        "Avatar download response received"
+    );
+
+    // Reject avatars that announce their size upfront before reading them
+    if content_length.is_some_and(|length| length > options.max_size) {
+        return Err(too_large());
+    }
+
+    // Read the body in chunks to enforce the size limit without a trusted
+    // `Content-Length` header
+    let mut bytes = BytesMut::new();
+    while let Some(chunk) = response.chunk().await.map_err(download_error)? {
+        if (bytes.len() + chunk.len()) as u64 > options.max_size {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(Some(bytes.freeze()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    fn fast_retries() -> AvatarFetchOptions {
+        AvatarFetchOptions::default().with_retry_backoff(Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn test_download_avatar() {
+        let mut server = Server::new_async().await;
+        let avatar = include_bytes!("../template/assets/test-avatar.png");
+        let mock = server
+            .mock("GET", "/avatar.png")
+            .with_body(avatar)
+            .create_async()
+            .await;
+
+        let options = AvatarFetchOptions::default();
+        let client = options.build_client().unwrap();
+        let url = format!("{}/avatar.png", server.url());
+        let result = download_avatar(&client, &url, &options).await.unwrap();
+        let (extension, bytes) = result.unwrap();
+        assert_eq!(extension, "png");
+        assert_eq!(bytes, avatar.as_slice());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_avatar_too_large() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/avatar.png")
+            .with_body(include_bytes!("../template/assets/test-avatar.png"))
+            .create_async()
+            .await;
+
+        let options = AvatarFetchOptions::default().with_max_size(100);
+        let client = options.build_client().unwrap();
+        let url = format!("{}/avatar.png", server.url());
+        let error = download_avatar(&client, &url, &options).await.unwrap_err();
+        assert!(matches!(
+            error,
+            OgImageError::AvatarTooLarge { max_size: 100, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_download_avatar_server_error() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/avatar.png")
+            .with_status(503)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let options = fast_retries();
+        let client = options.build_client().unwrap();
+        let url = format!("{}/avatar.png", server.url());
+        let error = download_avatar(&client, &url, &options).await.unwrap_err();
+        assert!(matches!(
+            error,
+            OgImageError::AvatarServerError {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                attempts: 3,
+                ..
+            }
+        ));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_avatar_timeout() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/avatar.png")
+            .with_chunked_body(|_| {
+                std::thread::sleep(Duration::from_millis(500));
+                Ok(())
+            })
+            .create_async()
+            .await;
+
+        let options = fast_retries()
+            .with_timeout(Duration::from_millis(50))
+            .with_max_retries(1);
+        let client = options.build_client().unwrap();
+        let url = format!("{}/avatar.png", server.url());
+        let error = download_avatar(&client, &url, &options).await.unwrap_err();
+        assert!(matches!(
+            error,
+            OgImageError::AvatarTimeout { attempts: 2, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_download_avatar_not_found() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/avatar.png")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let options = fast_retries();
+        let client = options.build_client().unwrap();
+        let url = format!("{}/avatar.png", server.url());
+        let result = download_avatar(&client, &url, &options).await.unwrap();
+        assert!(result.is_none());
+        mock.assert_async().await;
+    }
+}
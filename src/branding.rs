@@ -0,0 +1,227 @@
+//! Branding of the card header and watermark.
+
+use crate::{OgImageError, OgImageGenerator};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// Path of the bundled crates.io header logo.
+const DEFAULT_LOGO_PATH: &str = "assets/cargo.png";
+
+/// Path of the bundled Rust logo watermark.
+const DEFAULT_WATERMARK_PATH: &str = "assets/rust-logo.svg";
+
+/// The registry name, header logo and watermark shown on the cards.
+///
+/// Defaults to the crates.io branding. Alternate registries can replace the
+/// name, the logo and the watermark individually.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crates_io_og_image::{Branding, OgImageError, OgImageGenerator};
+///
+/// # fn main() -> Result<(), OgImageError> {
+/// let logo = std::fs::read("registry-logo.png")?;
+/// let watermark = std::fs::read("registry-watermark.svg")?;
+///
+/// let branding = Branding::new("registry.example.com")
+///     .with_logo(logo)?
+///     .with_watermark(watermark)?;
+///
+/// let generator = OgImageGenerator::default().with_branding(branding);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Branding {
+    name: Cow<'static, str>,
+    logo: Option<(String, Cow<'static, [u8]>)>,
+    watermark: Option<Cow<'static, [u8]>>,
+}
+
+impl Branding {
+    /// Creates a new branding with the given registry name, which is shown
+    /// next to the logo in the header.
+    ///
+    /// The bundled Cargo logo and Rust watermark are used until they are
+    /// replaced with [`with_logo()`](Self::with_logo) and
+    /// [`with_watermark()`](Self::with_watermark).
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            logo: None,
+            watermark: None,
+        }
+    }
+
+    /// Returns the crates.io branding.
+    pub fn crates_io() -> Self {
+        Self::new("crates.io")
+    }
+
+    /// Sets the logo shown in the header.
+    ///
+    /// The logo must be a PNG, JPEG or SVG image. It is scaled to a width
+    /// of 35pt.
+    pub fn with_logo(mut self, logo: impl Into<Cow<'static, [u8]>>) -> Result<Self, OgImageError> {
+        let logo = logo.into();
+
+        let extension = OgImageGenerator::detect_image_format(&logo)
+            .or_else(|| is_svg(&logo).then_some("svg"))
+            .ok_or_else(|| {
+                let message = "Logo must be a PNG, JPEG or SVG image".to_string();
+                OgImageError::InvalidBranding(message)
+            })?;
+
+        self.logo = Some((format!("assets/branding/logo.{extension}"), logo));
+        Ok(self)
+    }
+
+    /// Sets the watermark shown in the bottom right corner of the card.
+    ///
+    /// The watermark must be an SVG image. Any `currentColor` in the SVG is
+    /// replaced with the watermark color of the [`Theme`](crate::Theme).
+    pub fn with_watermark(
+        mut self,
+        watermark: impl Into<Cow<'static, [u8]>>,
+    ) -> Result<Self, OgImageError> {
+        let watermark = watermark.into();
+
+        // The template replaces `currentColor` in the SVG source, so the
+        // watermark has to be valid UTF-8
+        if !is_svg(&watermark) || std::str::from_utf8(&watermark).is_err() {
+            let message = "Watermark must be an SVG image".to_string();
+            return Err(OgImageError::InvalidBranding(message));
+        }
+
+        self.watermark = Some(watermark);
+        Ok(self)
+    }
+
+    /// Returns the registry name shown in the header.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the `branding` input of the template and adds the custom
+    /// logo and watermark to its `assets`.
+    pub(crate) fn to_input(
+        &self,
+        assets: &mut BTreeMap<String, Cow<'static, [u8]>>,
+    ) -> Result<String, OgImageError> {
+        let logo = match &self.logo {
+            Some((path, bytes)) => {
+                assets.insert(path.clone(), bytes.clone());
+                path.as_str()
+            }
+            None => DEFAULT_LOGO_PATH,
+        };
+
+        let watermark = match &self.watermark {
+            Some(bytes) => {
+                let path = "assets/branding/watermark.svg";
+                assets.insert(path.to_string(), bytes.clone());
+                path
+            }
+            None => DEFAULT_WATERMARK_PATH,
+        };
+
+        let branding = serde_json::json!({
+            "name": self.name,
+            "logo": logo,
+            "watermark": watermark,
+        });
+
+        serde_json::to_string(&branding).map_err(OgImageError::JsonSerializationError)
+    }
+}
+
+impl Default for Branding {
+    /// Returns the [crates.io](Self::crates_io) branding.
+    fn default() -> Self {
+        Self::crates_io()
+    }
+}
+
+/// Checks whether the bytes look like an SVG document.
+fn is_svg(bytes: &[u8]) -> bool {
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return false;
+    };
+
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    (text.starts_with("<?xml") || text.starts_with("<svg")) && text.contains("<svg")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SVG: &[u8] = br#"<svg xmlns="http://www.w3.org/2000/svg"/>"#;
+
+    #[test]
+    fn test_default_to_input() {
+        let mut assets = BTreeMap::new();
+        let input = Branding::default().to_input(&mut assets).unwrap();
+        assert!(assets.is_empty());
+
+        let input: serde_json::Value = serde_json::from_str(&input).unwrap();
+        let expected = serde_json::json!({
+            "name": "crates.io",
+            "logo": "assets/cargo.png",
+            "watermark": "assets/rust-logo.svg",
+        });
+        assert_eq!(input, expected);
+    }
+
+    #[test]
+    fn test_custom_to_input() {
+        let png = include_bytes!("../template/assets/cargo.png");
+        let branding = Branding::new("registry.example.com")
+            .with_logo(png.as_slice())
+            .unwrap()
+            .with_watermark(SVG)
+            .unwrap();
+
+        let mut assets = BTreeMap::new();
+        let input = branding.to_input(&mut assets).unwrap();
+        assert_eq!(assets["assets/branding/logo.png"], png.as_slice());
+        assert_eq!(assets["assets/branding/watermark.svg"], SVG);
+
+        let input: serde_json::Value = serde_json::from_str(&input).unwrap();
+        let expected = serde_json::json!({
+            "name": "registry.example.com",
+            "logo": "assets/branding/logo.png",
+            "watermark": "assets/branding/watermark.svg",
+        });
+        assert_eq!(input, expected);
+    }
+
+    #[test]
+    fn test_with_logo_svg() {
+        let branding = Branding::crates_io().with_logo(SVG).unwrap();
+        let (path, _) = branding.logo.unwrap();
+        assert_eq!(path, "assets/branding/logo.svg");
+    }
+
+    #[test]
+    fn test_invalid_images() {
+        assert!(
+            Branding::crates_io()
+                .with_logo(b"GIF89a".as_slice())
+                .is_err()
+        );
+        assert!(
+            Branding::crates_io()
+                .with_watermark(b"<html/>".as_slice())
+                .is_err()
+        );
+
+        let png = include_bytes!("../template/assets/cargo.png");
+        assert!(
+            Branding::crates_io()
+                .with_watermark(png.as_slice())
+                .is_err()
+        );
+    }
+}
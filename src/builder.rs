@@ -0,0 +1,385 @@
+//! Builder and input validation for the image data.
+
+use crate::{OgImageAuthorDataOwned, OgImageDataOwned, ValidationError};
+
+/// Builder for validated [`OgImageDataOwned`], created by
+/// [`OgImageData::builder()`](crate::OgImageData::builder).
+///
+/// # Examples
+///
+/// ```
+/// use crates_io_og_image::OgImageData;
+///
+/// let data = OgImageData::builder("serde", "1.0.210")
+///     .with_description("  A generic serialization/deserialization framework\n")
+///     .with_license("MIT OR Apache-2.0")
+///     .with_tags(["serde", "serialization"])
+///     .with_author("dtolnay", Some("https://avatars.githubusercontent.com/u/1940490"))
+///     .with_crate_size(78_000)
+///     .with_releases(300)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(
+///     data.description.as_deref(),
+///     Some("A generic serialization/deserialization framework")
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct OgImageDataBuilder {
+    data: OgImageDataOwned,
+}
+
+impl OgImageDataBuilder {
+    /// The maximum length of the crate name in characters, matching crates.io.
+    pub const MAX_NAME_LENGTH: usize = 64;
+
+    /// The maximum length of the version string in characters.
+    pub const MAX_VERSION_LENGTH: usize = 64;
+
+    /// The maximum length of the description in characters.
+    pub const MAX_DESCRIPTION_LENGTH: usize = 1000;
+
+    /// The maximum length of the license expression in characters.
+    pub const MAX_LICENSE_LENGTH: usize = 256;
+
+    /// The maximum number of tags.
+    pub const MAX_TAGS: usize = 10;
+
+    /// The maximum length of a tag in characters.
+    pub const MAX_TAG_LENGTH: usize = 50;
+
+    /// The maximum number of authors.
+    pub const MAX_AUTHORS: usize = 50;
+
+    /// The maximum length of an author name in characters.
+    pub const MAX_AUTHOR_NAME_LENGTH: usize = 100;
+
+    /// The maximum length of an avatar URL in characters.
+    pub const MAX_AVATAR_URL_LENGTH: usize = 2048;
+
+    /// The maximum number of download history data points, e.g. one per day
+    /// of a year.
+    pub const MAX_DOWNLOAD_HISTORY: usize = 366;
+
+    pub(crate) fn new(name: String, version: String) -> Self {
+        Self {
+            data: OgImageDataOwned {
+                name,
+                version,
+                description: None,
+                license: None,
+                tags: Vec::new(),
+                authors: Vec::new(),
+                lines_of_code: None,
+                crate_size: 0,
+                releases: 0,
+                downloads: None,
+                recent_downloads: None,
+                download_history: Vec::new(),
+            },
+        }
+    }
+
+    /// Sets the crate description.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.data.description = Some(description.into());
+        self
+    }
+
+    /// Sets the license expression.
+    pub fn with_license(mut self, license: impl Into<String>) -> Self {
+        self.data.license = Some(license.into());
+        self
+    }
+
+    /// Adds a tag.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.data.tags.push(tag.into());
+        self
+    }
+
+    /// Adds multiple tags.
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.data.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds an author with an optional avatar URL.
+    pub fn with_author(mut self, name: impl Into<String>, avatar: Option<&str>) -> Self {
+        self.data.authors.push(OgImageAuthorDataOwned {
+            name: name.into(),
+            avatar: avatar.map(str::to_string),
+        });
+        self
+    }
+
+    /// Sets the source lines of code count.
+    pub fn with_lines_of_code(mut self, lines_of_code: u32) -> Self {
+        self.data.lines_of_code = Some(lines_of_code);
+        self
+    }
+
+    /// Sets the package size in bytes.
+    pub fn with_crate_size(mut self, crate_size: u64) -> Self {
+        self.data.crate_size = crate_size;
+        self
+    }
+
+    /// Sets the total number of releases.
+    pub fn with_releases(mut self, releases: u32) -> Self {
+        self.data.releases = releases;
+        self
+    }
+
+    /// Sets the total number of downloads.
+    pub fn with_downloads(mut self, downloads: u64) -> Self {
+        self.data.downloads = Some(downloads);
+        self
+    }
+
+    /// Sets the number of downloads in the last 90 days.
+    pub fn with_recent_downloads(mut self, recent_downloads: u64) -> Self {
+        self.data.recent_downloads = Some(recent_downloads);
+        self
+    }
+
+    /// Sets the download counts in chronological order, e.g. 90 daily buckets.
+    pub fn with_download_history(
+        mut self,
+        download_history: impl IntoIterator<Item = u64>,
+    ) -> Self {
+        self.data.download_history = download_history.into_iter().collect();
+        self
+    }
+
+    /// Sanitizes and validates the data.
+    ///
+    /// See [`OgImageDataOwned::validate()`] for the applied rules.
+    pub fn build(self) -> Result<OgImageDataOwned, ValidationError> {
+        self.data.validate()
+    }
+}
+
+impl OgImageDataOwned {
+    /// Sanitizes and validates the data, e.g. after deserializing it from an
+    /// untrusted source.
+    ///
+    /// All strings are trimmed, line breaks and tabs are replaced with
+    /// spaces, and other control characters are removed. Afterwards, the
+    /// name, version, tags and author names must not be empty, and all
+    /// strings and the number of tags and authors must not exceed the limits
+    /// defined on [`OgImageDataBuilder`], e.g.
+    /// [`MAX_DESCRIPTION_LENGTH`](OgImageDataBuilder::MAX_DESCRIPTION_LENGTH). Empty
+    /// descriptions and licenses are treated as missing.
+    pub fn validate(self) -> Result<Self, ValidationError> {
+        let name = required("name", &self.name, OgImageDataBuilder::MAX_NAME_LENGTH)?;
+        let version = required(
+            "version",
+            &self.version,
+            OgImageDataBuilder::MAX_VERSION_LENGTH,
+        )?;
+        let description = optional(
+            "description",
+            self.description,
+            OgImageDataBuilder::MAX_DESCRIPTION_LENGTH,
+        )?;
+        let license = optional(
+            "license",
+            self.license,
+            OgImageDataBuilder::MAX_LICENSE_LENGTH,
+        )?;
+
+        if self.tags.len() > OgImageDataBuilder::MAX_TAGS {
+            let (count, max) = (self.tags.len(), OgImageDataBuilder::MAX_TAGS);
+            return Err(ValidationError::TooManyTags { count, max });
+        }
+
+        let tags = self
+            .tags
+            .iter()
+            .map(|tag| required("tag", tag, OgImageDataBuilder::MAX_TAG_LENGTH));
+        let tags = tags.collect::<Result<Vec<_>, _>>()?;
+
+        if self.authors.len() > OgImageDataBuilder::MAX_AUTHORS {
+            let (count, max) = (self.authors.len(), OgImageDataBuilder::MAX_AUTHORS);
+            return Err(ValidationError::TooManyAuthors { count, max });
+        }
+
+        let authors = self.authors.into_iter().map(|author| {
+            Ok(OgImageAuthorDataOwned {
+                name: required(
+                    "author name",
+                    &author.name,
+                    OgImageDataBuilder::MAX_AUTHOR_NAME_LENGTH,
+                )?,
+                avatar: optional(
+                    "avatar URL",
+                    author.avatar,
+                    OgImageDataBuilder::MAX_AVATAR_URL_LENGTH,
+                )?,
+            })
+        });
+        let authors = authors.collect::<Result<Vec<_>, _>>()?;
+
+        let count = self.download_history.len();
+        let max = OgImageDataBuilder::MAX_DOWNLOAD_HISTORY;
+        if count > max {
+            return Err(ValidationError::TooManyDataPoints { count, max });
+        }
+
+        Ok(Self {
+            name,
+            version,
+            description,
+            license,
+            tags,
+            authors,
+            ..self
+        })
+    }
+}
+
+/// Trims the value and removes control characters.
+fn sanitize(value: &str) -> String {
+    let value = value.chars().filter_map(|c| match c {
+        '\n' | '\r' | '\t' => Some(' '),
+        c if c.is_control() => None,
+        c => Some(c),
+    });
+
+    value.collect::<String>().trim().to_string()
+}
+
+/// Sanitizes a required value and checks that it is neither empty nor too long.
+fn required(field: &'static str, value: &str, max: usize) -> Result<String, ValidationError> {
+    let value = sanitize(value);
+    if value.is_empty() {
+        return Err(ValidationError::Empty { field });
+    }
+
+    check_length(field, value, max)
+}
+
+/// Sanitizes an optional value, treating empty values as missing.
+fn optional(
+    field: &'static str,
+    value: Option<String>,
+    max: usize,
+) -> Result<Option<String>, ValidationError> {
+    let value = value.map(|value| sanitize(&value));
+    let value = value.filter(|value| !value.is_empty());
+    value
+        .map(|value| check_length(field, value, max))
+        .transpose()
+}
+
+fn check_length(field: &'static str, value: String, max: usize) -> Result<String, ValidationError> {
+    let length = value.chars().count();
+    if length > max {
+        return Err(ValidationError::TooLong { field, length, max });
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OgImageData;
+
+    #[test]
+    fn test_build() {
+        let data = OgImageData::builder(" serde\n", "1.0.210")
+            .with_description("A\u{0}\tframework\u{7f}  ")
+            .with_license("")
+            .with_tags(["serde", "serialization"])
+            .with_author("dtolnay", None)
+            .with_lines_of_code(1000)
+            .with_crate_size(42012)
+            .with_releases(7)
+            .with_downloads(123_456)
+            .build()
+            .unwrap();
+
+        assert_eq!(data.name, "serde");
+        assert_eq!(data.description.as_deref(), Some("A framework"));
+        assert_eq!(data.license, None);
+        assert_eq!(data.tags, ["serde", "serialization"]);
+        assert_eq!(data.authors[0].name, "dtolnay");
+        assert_eq!(data.lines_of_code, Some(1000));
+        assert_eq!(data.crate_size, 42012);
+        assert_eq!(data.releases, 7);
+        assert_eq!(data.downloads, Some(123_456));
+        assert_eq!(data.recent_downloads, None);
+    }
+
+    #[test]
+    fn test_empty_values() {
+        let result = OgImageData::builder(" \n", "1.0.0").build();
+        let error = result.unwrap_err();
+        assert!(matches!(error, ValidationError::Empty { field: "name" }));
+
+        let result = OgImageData::builder("serde", "1.0.0")
+            .with_tag("\t")
+            .build();
+        let error = result.unwrap_err();
+        assert!(matches!(error, ValidationError::Empty { field: "tag" }));
+    }
+
+    #[test]
+    fn test_too_long_values() {
+        let description = "a".repeat(OgImageDataBuilder::MAX_DESCRIPTION_LENGTH + 1);
+        let builder = OgImageData::builder("serde", "1.0.0").with_description(description);
+        let error = builder.build().unwrap_err();
+        assert!(matches!(
+            error,
+            ValidationError::TooLong {
+                field: "description",
+                length: 1001,
+                max: OgImageDataBuilder::MAX_DESCRIPTION_LENGTH,
+            }
+        ));
+
+        // Lengths are counted in characters, not bytes
+        let name = "ä".repeat(OgImageDataBuilder::MAX_NAME_LENGTH);
+        assert!(OgImageData::builder(name, "1.0.0").build().is_ok());
+    }
+
+    #[test]
+    fn test_too_long_download_history() {
+        let max = OgImageDataBuilder::MAX_DOWNLOAD_HISTORY;
+
+        let builder = OgImageData::builder("serde", "1.0.0");
+        let builder = builder.with_download_history((0..max as u64).rev());
+        assert_eq!(builder.build().unwrap().download_history.len(), max);
+
+        let builder = OgImageData::builder("serde", "1.0.0");
+        let builder = builder.with_download_history(vec![0; max + 1]);
+        assert!(matches!(
+            builder.build().unwrap_err(),
+            ValidationError::TooManyDataPoints { count: 367, .. }
+        ));
+    }
+
+    #[test]
+    fn test_too_many_tags_and_authors() {
+        let tags = (0..=OgImageDataBuilder::MAX_TAGS).map(|i| format!("tag-{i}"));
+        let error = OgImageData::builder("serde", "1.0.0")
+            .with_tags(tags)
+            .build();
+        assert!(matches!(
+            error.unwrap_err(),
+            ValidationError::TooManyTags { count: 11, .. }
+        ));
+
+        let mut builder = OgImageData::builder("serde", "1.0.0");
+        for i in 0..=OgImageDataBuilder::MAX_AUTHORS {
+            builder = builder.with_author(format!("author-{i}"), None);
+        }
+        assert!(matches!(
+            builder.build().unwrap_err(),
+            ValidationError::TooManyAuthors { count: 51, .. }
+        ));
+    }
+}
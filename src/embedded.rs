@@ -0,0 +1,307 @@
+//! In-process Typst compilation.
+//!
+//! This module contains a minimal [`World`] implementation that serves the
+//! template, the bundled assets and the downloaded avatars from memory, so
+//! that images can be rendered without spawning the `typst` CLI.
+//!
+//! Compiling and exporting the template is CPU-bound, so it runs on the
+//! blocking thread pool instead of stalling the async runtime. The same
+//! applies to the in-memory PNG optimization and the transcoding.
+
+use crate::renderer::{RenderFuture, RenderRequest, Renderer};
+use crate::{OgImageError, RenderFormat};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tracing::{debug, error, info};
+use typst::diag::{FileError, FileResult, SourceDiagnostic};
+use typst::foundations::{Bytes, Datetime, Dict, Str, Value};
+use typst::layout::PagedDocument;
+use typst::syntax::{FileId, Source, VirtualPath};
+use typst::text::{Font, FontBook};
+use typst::utils::LazyHash;
+use typst::{Library, World};
+use typst_kit::fonts::{FontSearcher, Fonts};
+use typst_pdf::PdfOptions;
+
+/// The path of the template entry file, relative to the virtual project root.
+const MAIN_PATH: &str = "og-image.typ";
+
+/// Fonts discovered for in-process compilation.
+///
+/// Font discovery is comparatively expensive, so the result is cached and
+/// shared between all compilations of a [`TypstEmbeddedRenderer`].
+#[derive(Default)]
+pub(crate) struct FontCache {
+    fonts: OnceLock<Arc<Fonts>>,
+}
+
+impl FontCache {
+    /// Returns the cached fonts, searching for them on first use.
+    ///
+    /// If a font path is given, only fonts from that directory are used,
+    /// mirroring the `--font-path` and `--ignore-system-fonts` flags that
+    /// are passed to the Typst CLI. Like in the Typst CLI, the fonts that are
+    /// embedded in Typst are always available as a fallback.
+    fn get(&self, font_path: Option<&Path>) -> Arc<Fonts> {
+        let fonts = self.fonts.get_or_init(|| {
+            let start_time = std::time::Instant::now();
+
+            let fonts = FontSearcher::new()
+                .include_system_fonts(font_path.is_none())
+                .search_with(font_path);
+
+            debug!(
+                font_count = fonts.fonts.len(),
+                duration_ms = start_time.elapsed().as_millis(),
+                "Font discovery completed"
+            );
+
+            Arc::new(fonts)
+        });
+
+        fonts.clone()
+    }
+
+    /// Returns the characters that are not covered by any of the font
+    /// `families`, ignoring whitespace and invisible formatting characters.
+    ///
+    /// Families that are not installed are skipped, like Typst does when
+    /// it looks up the fonts of a `text` element.
+    pub(crate) fn missing_chars(
+        &self,
+        font_path: Option<&Path>,
+        families: &[Cow<'static, str>],
+        chars: impl IntoIterator<Item = char>,
+    ) -> BTreeSet<char> {
+        let fonts = self.get(font_path);
+
+        let infos = families
+            .iter()
+            .flat_map(|family| {
+                let family = family.to_lowercase();
+                fonts.book.select_family(&family).collect::<Vec<_>>()
+            })
+            .filter_map(|index| fonts.book.info(index))
+            .collect::<Vec<_>>();
+
+        chars
+            .into_iter()
+            .filter(|c| !c.is_whitespace() && !c.is_control() && !is_invisible(*c))
+            .filter(|c| !infos.iter().any(|info| info.coverage.contains(*c as u32)))
+            .collect()
+    }
+}
+
+/// Checks whether the character is an invisible formatting character, e.g.
+/// a zero width joiner or variation selector in an emoji sequence, which
+/// fonts usually do not contain glyphs for.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{E0000}'..='\u{E01EF}'
+    )
+}
+
+/// A [`Renderer`] that compiles the template in-process using the `typst` crates.
+///
+/// Fonts are discovered on first use and cached for the lifetime of the
+/// renderer and its clones.
+#[derive(Clone, Default)]
+pub struct TypstEmbeddedRenderer {
+    font_path: Option<PathBuf>,
+    font_cache: Arc<FontCache>,
+}
+
+impl TypstEmbeddedRenderer {
+    /// Creates a new `TypstEmbeddedRenderer` with an optional font path.
+    ///
+    /// If a font path is given, system fonts are ignored.
+    pub fn new(font_path: Option<PathBuf>) -> Self {
+        let font_cache = Default::default();
+        Self::with_font_cache(font_path, font_cache)
+    }
+
+    /// Creates a new `TypstEmbeddedRenderer` that shares the given font cache.
+    pub(crate) fn with_font_cache(font_path: Option<PathBuf>, font_cache: Arc<FontCache>) -> Self {
+        Self {
+            font_path,
+            font_cache,
+        }
+    }
+}
+
+impl TypstEmbeddedRenderer {
+    /// Compiles the request once and exports it at every pixel density in `ppis`.
+    async fn render_images(
+        &self,
+        request: RenderRequest,
+        ppis: Vec<f32>,
+    ) -> Result<Vec<Vec<u8>>, OgImageError> {
+        info!("Compiling Typst template in-process");
+
+        let font_path = self.font_path.clone();
+        let font_cache = self.font_cache.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let font_path = font_path.as_deref();
+            compile(request, &ppis, &font_cache, font_path)
+        })
+        .await
+        .map_err(OgImageError::RenderTaskError)?
+    }
+}
+
+impl Renderer for TypstEmbeddedRenderer {
+    fn render(&self, request: RenderRequest) -> RenderFuture<'_> {
+        Box::pin(async move {
+            let ppis = vec![request.ppi];
+            let mut images = self.render_images(request, ppis).await?;
+            Ok(images.remove(0))
+        })
+    }
+
+    fn render_densities(
+        &self,
+        request: RenderRequest,
+        ppis: Vec<f32>,
+    ) -> RenderFuture<'_, Vec<Vec<u8>>> {
+        Box::pin(self.render_images(request, ppis))
+    }
+}
+
+/// A [`World`] that serves all files from memory.
+struct OgImageWorld {
+    library: LazyHash<Library>,
+    book: LazyHash<FontBook>,
+    fonts: Arc<Fonts>,
+    main: Source,
+    files: BTreeMap<String, Bytes>,
+}
+
+impl World for OgImageWorld {
+    fn library(&self) -> &LazyHash<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &LazyHash<FontBook> {
+        &self.book
+    }
+
+    fn main(&self) -> FileId {
+        self.main.id()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        if id == self.main.id() {
+            return Ok(self.main.clone());
+        }
+
+        let bytes = self.file(id)?;
+        let text = std::str::from_utf8(&bytes).map_err(|_| FileError::InvalidUtf8)?;
+        Ok(Source::new(id, text.to_string()))
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        let path = id.vpath().as_rootless_path();
+        if id.package().is_some() {
+            return Err(FileError::NotFound(path.to_path_buf()));
+        }
+
+        let key = path.to_string_lossy();
+        let bytes = self.files.get(key.as_ref());
+        bytes
+            .cloned()
+            .ok_or_else(|| FileError::NotFound(path.to_path_buf()))
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.fonts.fonts.get(index)?.get()
+    }
+
+    fn today(&self, _offset: Option<i64>) -> Option<Datetime> {
+        None
+    }
+}
+
+/// Compiles the template of the request in-process and exports the first
+/// page in the requested format once for every pixel density in `ppis`.
+fn compile(
+    request: RenderRequest,
+    ppis: &[f32],
+    font_cache: &FontCache,
+    font_path: Option<&Path>,
+) -> Result<Vec<Vec<u8>>, OgImageError> {
+    let RenderRequest {
+        template,
+        inputs,
+        assets,
+        format,
+        ppi: _,
+    } = request;
+
+    let mut dict = Dict::new();
+    for (key, value) in inputs {
+        dict.insert(Str::from(key), Value::Str(Str::from(value)));
+    }
+
+    let fonts = font_cache.get(font_path);
+    let files = assets.into_iter();
+    let files = files.map(|(path, bytes)| (path, Bytes::new(bytes)));
+
+    let world = OgImageWorld {
+        library: LazyHash::new(Library::builder().with_inputs(dict).build()),
+        book: LazyHash::new(fonts.book.clone()),
+        main: Source::new(
+            FileId::new(None, VirtualPath::new(MAIN_PATH)),
+            template.into_owned(),
+        ),
+        files: files.collect(),
+        fonts,
+    };
+
+    let document = typst::compile::<PagedDocument>(&world).output;
+    let document = document.map_err(|diagnostics| diagnostics_error(&diagnostics))?;
+
+    let page = document.pages.first().ok_or_else(|| {
+        let message = "Typst document does not contain any pages".to_string();
+        OgImageError::TypstDiagnosticError { message }
+    })?;
+
+    let export = |ppi: f32| match format {
+        RenderFormat::Png => {
+            let pixmap = typst_render::render(page, ppi / 72.);
+            pixmap
+                .encode_png()
+                .map_err(|err| OgImageError::PngEncodingError(err.to_string()))
+        }
+        // Images are embedded as data URLs, so the SVG is self-contained
+        RenderFormat::Svg => Ok(typst_svg::svg(page).into_bytes()),
+        // Fonts are embedded as subsets, so the PDF is self-contained
+        RenderFormat::Pdf => {
+            let options = PdfOptions::default();
+            let pdf = typst_pdf::pdf(&document, &options);
+            pdf.map_err(|diagnostics| diagnostics_error(&diagnostics))
+        }
+    };
+
+    ppis.iter().map(|ppi| export(*ppi)).collect()
+}
+
+fn diagnostics_error(diagnostics: &[SourceDiagnostic]) -> OgImageError {
+    let message = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    error!(message = %message, "Typst compilation failed");
+    OgImageError::TypstDiagnosticError { message }
+}
@@ -1,5 +1,6 @@
 //! Error types for the crates_io_og_image crate.
 
+use crate::OutputFormat;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -22,18 +23,68 @@ pub enum OgImageError {
         source: reqwest::Error,
     },
 
-    /// Failed to write avatar to file.
-    #[error("Failed to write avatar to file at {path:?}: {source}")]
-    AvatarWriteError {
-        path: PathBuf,
-        #[source]
-        source: std::io::Error,
+    /// Failed to create the HTTP client for the avatar downloads.
+    #[error("Failed to create HTTP client: {0}")]
+    HttpClientError(#[source] reqwest::Error),
+
+    /// The avatar download timed out, including all retries.
+    #[error("Timed out downloading avatar from URL '{url}' after {attempts} attempts")]
+    AvatarTimeout { url: String, attempts: u32 },
+
+    /// The avatar server responded with a server error, including all retries.
+    #[error("Avatar server for URL '{url}' responded with {status} after {attempts} attempts")]
+    AvatarServerError {
+        url: String,
+        status: reqwest::StatusCode,
+        attempts: u32,
     },
 
+    /// The avatar exceeds the maximum avatar size.
+    #[error("Avatar at URL '{url}' exceeds the maximum size of {max_size} bytes")]
+    AvatarTooLarge { url: String, max_size: u64 },
+
     /// JSON serialization error.
     #[error("JSON serialization error: {0}")]
     JsonSerializationError(#[source] serde_json::Error),
 
+    /// The template is invalid, e.g. because it references unknown inputs.
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
+
+    /// Failed to read a file of the template directory.
+    #[error("Failed to read template file {path}: {source}")]
+    TemplateReadError {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The image data failed validation.
+    #[error("Invalid image data: {0}")]
+    InvalidData(#[from] ValidationError),
+
+    /// The logo or watermark of the branding is not a supported image.
+    #[error("Invalid branding: {0}")]
+    InvalidBranding(String),
+
+    /// The color is not a valid hex color.
+    #[error("Invalid color: {0}")]
+    InvalidColor(String),
+
+    /// The pixel density is not a finite positive number.
+    #[error("Invalid pixel density {0}, expected a positive number of pixels per inch")]
+    InvalidPpi(f32),
+
+    /// A text color of the theme does not meet the required contrast ratio.
+    #[error(
+        "Insufficient contrast of the theme's {element} color: {ratio:.2}:1, expected at least {required}:1"
+    )]
+    InsufficientContrast {
+        element: &'static str,
+        ratio: f64,
+        required: f64,
+    },
+
     /// Typst compilation failed.
     #[error("Typst compilation failed: {stderr}")]
     TypstCompilationError {
@@ -42,6 +93,33 @@ pub enum OgImageError {
         exit_code: Option<i32>,
     },
 
+    /// In-process Typst compilation failed.
+    #[error("Typst compilation failed: {message}")]
+    TypstDiagnosticError { message: String },
+
+    /// Failed to encode the rendered image as PNG.
+    #[error("Failed to encode PNG: {0}")]
+    PngEncodingError(String),
+
+    /// Failed to transcode the rendered image into the output format.
+    #[error("Failed to encode image: {0}")]
+    ImageEncodingError(String),
+
+    /// The output format requires a cargo feature that is not enabled.
+    #[error("Output format {format:?} requires the `{feature}` feature")]
+    FormatUnavailable {
+        format: OutputFormat,
+        feature: &'static str,
+    },
+
+    /// The blocking rendering task panicked or was cancelled.
+    #[error("Rendering task failed: {0}")]
+    RenderTaskError(#[source] tokio::task::JoinError),
+
+    /// Failed to write the generated image into the output writer.
+    #[error("Failed to write image to output: {0}")]
+    OutputWriteError(#[source] std::io::Error),
+
     /// I/O error.
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
@@ -54,3 +132,32 @@ pub enum OgImageError {
     #[error("Failed to create temporary directory: {0}")]
     TempDirError(std::io::Error),
 }
+
+/// Errors that can occur when validating the image data with
+/// [`OgImageDataOwned::validate()`](crate::OgImageDataOwned::validate).
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ValidationError {
+    /// A required value is empty after sanitization.
+    #[error("The {field} must not be empty")]
+    Empty { field: &'static str },
+
+    /// A value exceeds its maximum length in characters.
+    #[error("The {field} is {length} characters long, expected at most {max}")]
+    TooLong {
+        field: &'static str,
+        length: usize,
+        max: usize,
+    },
+
+    /// There are more tags than allowed.
+    #[error("Found {count} tags, expected at most {max}")]
+    TooManyTags { count: usize, max: usize },
+
+    /// There are more authors than allowed.
+    #[error("Found {count} authors, expected at most {max}")]
+    TooManyAuthors { count: usize, max: usize },
+
+    /// The download history contains more data points than allowed.
+    #[error("Found {count} download history data points, expected at most {max}")]
+    TooManyDataPoints { count: usize, max: usize },
+}
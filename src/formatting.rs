@@ -1,28 +1,32 @@
 //! Module for number formatting functions.
 //!
 //! This module contains utility functions for formatting numbers in various ways,
-//! such as human-readable byte sizes.
+//! such as human-readable byte sizes, using the conventions of a [`Locale`].
 
+use crate::Locale;
 use serde::Serializer;
 
 /// Formats a byte size value into a human-readable string.
 ///
 /// The function follows these rules:
-/// - Uses units: B, KiB and MiB
+/// - Uses units: B, KiB, MiB, GiB, TiB, PiB and EiB
 /// - Switches from B to KiB at 1500 bytes
-/// - Switches from KiB to MiB at 1500 * 1024 bytes
-/// - Limits the number to a maximum of 4 characters by adjusting decimal places
+/// - Switches to the next unit at 1500 of the current unit, e.g. from KiB
+///   to MiB at 1500 * 1024 bytes
+/// - Limits the number to a maximum of 4 digits by adjusting decimal places
+/// - Uses the decimal and grouping separators of the locale
 ///
 /// # Arguments
 ///
 /// * `bytes` - The size in bytes to format
+/// * `locale` - The locale whose separators are used
 ///
 /// # Returns
 ///
 /// A formatted string representing the size with appropriate units
-pub fn format_bytes(bytes: u32) -> String {
+pub fn format_bytes(bytes: u64, locale: Locale) -> String {
     const THRESHOLD: f64 = 1500.;
-    const UNITS: &[&str] = &["B", "KiB", "MiB"];
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
 
     let mut value = bytes as f64;
     let mut unit_index = 0;
@@ -37,80 +41,98 @@ pub fn format_bytes(bytes: u32) -> String {
 
     // Special case for bytes - no decimal places
     if unit_index == 0 {
+        let bytes = locale.localize(&bytes.to_string());
         return format!("{bytes} {unit}");
     }
 
-    // For KiB and MiB, format with appropriate decimal places
+    // For KiB and larger units, format with appropriate decimal places
 
     // Determine number of decimal places to keep number under 4 chars
-    if value < 10.0 {
-        format!("{value:.2} {unit}") // e.g., 1.50 KiB, 9.99 MiB
+    let value = if value < 10.0 {
+        format!("{value:.2}") // e.g., 1.50 KiB, 9.99 MiB
     } else if value < 100.0 {
-        format!("{value:.1} {unit}") // e.g., 10.5 KiB, 99.9 MiB
+        format!("{value:.1}") // e.g., 10.5 KiB, 99.9 MiB
     } else {
-        format!("{value:.0} {unit}") // e.g., 100 KiB, 999 MiB
-    }
+        format!("{value:.0}") // e.g., 100 KiB, 999 MiB
+    };
+
+    let value = locale.localize(&value);
+    format!("{value} {unit}")
 }
 
-pub fn serialize_bytes<S: Serializer>(bytes: &u32, serializer: S) -> Result<S::Ok, S::Error> {
-    serializer.serialize_str(&format_bytes(*bytes))
+pub fn serialize_bytes<S: Serializer, N: Copy + Into<u64>>(
+    bytes: &N,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_bytes((*bytes).into(), Locale::English))
 }
 
-/// Formats a number with "k" and "M" suffixes for thousands and millions.
+/// Formats a number with suffixes for thousands, millions, billions and
+/// trillions, e.g. "K", "M", "B" and "T" in English.
 ///
 /// The function follows these rules:
-/// - Uses suffixes: none, k, and M
-/// - Switches from no suffix to k at 1500
-/// - Switches from k to M at 1500 * 1000
-/// - Limits the number to a maximum of 4 characters by adjusting decimal places
+/// - Uses the suffixes of the locale for ones, thousands, millions, billions
+///   and trillions, or for the myriad-based units 万, 億 and 兆 in Japanese
+/// - Switches from no suffix to thousands at 1500
+/// - Switches to the next suffix at 1500 of the current suffix, e.g. from
+///   thousands to millions at 1500 * 1000
+/// - Switches to the next myriad-based unit at 10000 of the current unit
+/// - Limits the number to a maximum of 4 digits by adjusting decimal places
+/// - Uses the decimal and grouping separators of the locale
 ///
 /// # Arguments
 ///
 /// * `number` - The number to format
+/// * `locale` - The locale whose suffixes and separators are used
 ///
 /// # Returns
 ///
 /// A formatted string representing the number with appropriate suffixes
-pub fn format_number(number: u32) -> String {
-    const THRESHOLD: f64 = 1500.;
-    const UNITS: &[&str] = &["", "K", "M"];
+pub fn format_number(number: u64, locale: Locale) -> String {
+    let (base, threshold, units) = locale.number_units();
 
     let mut value = number as f64;
     let mut unit_index = 0;
 
-    // Keep dividing by 1000 until value is below threshold or we've reached the last unit
-    while value >= THRESHOLD && unit_index < UNITS.len() - 1 {
-        value /= 1000.0;
+    // Keep dividing by the base until value is below threshold or we've reached the last unit
+    while value >= threshold && unit_index < units.len() - 1 {
+        value /= base;
         unit_index += 1;
     }
 
-    let unit = UNITS[unit_index];
+    let unit = units[unit_index];
 
     // Special case for numbers without suffix - no decimal places
     if unit_index == 0 {
-        return format!("{number}");
+        return locale.localize(&number.to_string());
     }
 
-    // For k and M, format with appropriate decimal places
+    // For thousands and larger, format with appropriate decimal places
 
     // Determine number of decimal places to keep number under 4 chars
-    if value < 10.0 {
-        format!("{value:.1}{unit}")
+    let value = if value < 10.0 {
+        format!("{value:.1}")
     } else {
-        format!("{value:.0}{unit}")
-    }
+        format!("{value:.0}")
+    };
+
+    let value = locale.localize(&value);
+    format!("{value}{unit}")
 }
 
-pub fn serialize_number<S: Serializer>(number: &u32, serializer: S) -> Result<S::Ok, S::Error> {
-    serializer.serialize_str(&format_number(*number))
+pub fn serialize_number<S: Serializer, N: Copy + Into<u64>>(
+    number: &N,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_number((*number).into(), Locale::English))
 }
 
-pub fn serialize_optional_number<S: Serializer>(
-    opt_number: &Option<u32>,
+pub fn serialize_optional_number<S: Serializer, N: Copy + Into<u64>>(
+    opt_number: &Option<N>,
     serializer: S,
 ) -> Result<S::Ok, S::Error> {
     match opt_number {
-        Some(number) => serializer.serialize_str(&format_number(*number)),
+        Some(number) => serializer.serialize_str(&format_number((*number).into(), Locale::English)),
         None => serializer.serialize_none(),
     }
 }
@@ -119,59 +141,148 @@ pub fn serialize_optional_number<S: Serializer>(
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_format_bytes() {
+    /// Test cases as `(bytes, English, German, Spanish, Japanese)`.
+    const BYTES_CASES: &[(u64, &str, &str, &str, &str)] = &[
         // Test bytes format (below 1500 bytes)
-        assert_eq!(format_bytes(0), "0 B");
-        assert_eq!(format_bytes(1), "1 B");
-        assert_eq!(format_bytes(1000), "1000 B");
-        assert_eq!(format_bytes(1499), "1499 B");
-
+        (0, "0 B", "0 B", "0 B", "0 B"),
+        (1, "1 B", "1 B", "1 B", "1 B"),
+        (1000, "1000 B", "1.000 B", "1.000 B", "1,000 B"),
+        (1499, "1499 B", "1.499 B", "1.499 B", "1,499 B"),
         // Test kilobytes format (1500 bytes to 1500 * 1024 bytes)
-        assert_eq!(format_bytes(1500), "1.46 KiB");
-        assert_eq!(format_bytes(2048), "2.00 KiB");
-        assert_eq!(format_bytes(5120), "5.00 KiB");
-        assert_eq!(format_bytes(10240), "10.0 KiB");
-        assert_eq!(format_bytes(51200), "50.0 KiB");
-        assert_eq!(format_bytes(102400), "100 KiB");
-        assert_eq!(format_bytes(512000), "500 KiB");
-        assert_eq!(format_bytes(1048575), "1024 KiB");
-
+        (1500, "1.46 KiB", "1,46 KiB", "1,46 KiB", "1.46 KiB"),
+        (2048, "2.00 KiB", "2,00 KiB", "2,00 KiB", "2.00 KiB"),
+        (5120, "5.00 KiB", "5,00 KiB", "5,00 KiB", "5.00 KiB"),
+        (10240, "10.0 KiB", "10,0 KiB", "10,0 KiB", "10.0 KiB"),
+        (51200, "50.0 KiB", "50,0 KiB", "50,0 KiB", "50.0 KiB"),
+        (102400, "100 KiB", "100 KiB", "100 KiB", "100 KiB"),
+        (512000, "500 KiB", "500 KiB", "500 KiB", "500 KiB"),
+        (1048575, "1024 KiB", "1.024 KiB", "1.024 KiB", "1,024 KiB"),
         // Test megabytes format (above 1500 * 1024 bytes)
-        assert_eq!(format_bytes(1536000), "1.46 MiB");
-        assert_eq!(format_bytes(2097152), "2.00 MiB");
-        assert_eq!(format_bytes(5242880), "5.00 MiB");
-        assert_eq!(format_bytes(10485760), "10.0 MiB");
-        assert_eq!(format_bytes(52428800), "50.0 MiB");
-        assert_eq!(format_bytes(104857600), "100 MiB");
-        assert_eq!(format_bytes(1073741824), "1024 MiB");
+        (1536000, "1.46 MiB", "1,46 MiB", "1,46 MiB", "1.46 MiB"),
+        (2097152, "2.00 MiB", "2,00 MiB", "2,00 MiB", "2.00 MiB"),
+        (5242880, "5.00 MiB", "5,00 MiB", "5,00 MiB", "5.00 MiB"),
+        (10485760, "10.0 MiB", "10,0 MiB", "10,0 MiB", "10.0 MiB"),
+        (52428800, "50.0 MiB", "50,0 MiB", "50,0 MiB", "50.0 MiB"),
+        (104857600, "100 MiB", "100 MiB", "100 MiB", "100 MiB"),
+        (
+            1073741824,
+            "1024 MiB",
+            "1.024 MiB",
+            "1.024 MiB",
+            "1,024 MiB",
+        ),
+        // Test gigabytes format (above 1500 * 1024^2 bytes)
+        (1610612736, "1.50 GiB", "1,50 GiB", "1,50 GiB", "1.50 GiB"),
+        (4294967296, "4.00 GiB", "4,00 GiB", "4,00 GiB", "4.00 GiB"),
+        (107374182400, "100 GiB", "100 GiB", "100 GiB", "100 GiB"),
+        (
+            1099511627776,
+            "1024 GiB",
+            "1.024 GiB",
+            "1.024 GiB",
+            "1,024 GiB",
+        ),
+        // Test terabytes format (above 1500 * 1024^3 bytes)
+        (
+            1649267441664,
+            "1.50 TiB",
+            "1,50 TiB",
+            "1,50 TiB",
+            "1.50 TiB",
+        ),
+        (
+            10995116277760,
+            "10.0 TiB",
+            "10,0 TiB",
+            "10,0 TiB",
+            "10.0 TiB",
+        ),
+        // Test petabytes format (above 1500 * 1024^4 bytes)
+        (
+            1649267441664000,
+            "1.46 PiB",
+            "1,46 PiB",
+            "1,46 PiB",
+            "1.46 PiB",
+        ),
+        (
+            1125899906842624000,
+            "1000 PiB",
+            "1.000 PiB",
+            "1.000 PiB",
+            "1,000 PiB",
+        ),
+        // Test exabytes format (above 1500 * 1024^5 bytes)
+        (
+            1729382256910270464,
+            "1.50 EiB",
+            "1,50 EiB",
+            "1,50 EiB",
+            "1.50 EiB",
+        ),
+        (u64::MAX, "16.0 EiB", "16,0 EiB", "16,0 EiB", "16.0 EiB"),
+    ];
+
+    /// Test cases as `(number, English, German, Spanish, Japanese)`.
+    const NUMBER_CASES: &[(u64, &str, &str, &str, &str)] = &[
+        // Test numbers without suffix (below 1500)
+        (0, "0", "0", "0", "0"),
+        (1, "1", "1", "1", "1"),
+        (1000, "1000", "1.000", "1.000", "1,000"),
+        (1499, "1499", "1.499", "1.499", "1,499"),
+        // Test numbers with thousands suffix (1500 to 1500 * 1000)
+        (1500, "1.5K", "1,5 Tsd.", "1,5 mil", "1,500"),
+        (2000, "2.0K", "2,0 Tsd.", "2,0 mil", "2,000"),
+        (5000, "5.0K", "5,0 Tsd.", "5,0 mil", "5,000"),
+        (10000, "10K", "10 Tsd.", "10 mil", "1.0万"),
+        (50000, "50K", "50 Tsd.", "50 mil", "5.0万"),
+        (100000, "100K", "100 Tsd.", "100 mil", "10万"),
+        (500000, "500K", "500 Tsd.", "500 mil", "50万"),
+        (999999, "1000K", "1.000 Tsd.", "1.000 mil", "100万"),
+        // Test numbers with millions suffix (above 1500 * 1000)
+        (1500000, "1.5M", "1,5 Mio.", "1,5 M", "150万"),
+        (2000000, "2.0M", "2,0 Mio.", "2,0 M", "200万"),
+        (5000000, "5.0M", "5,0 Mio.", "5,0 M", "500万"),
+        (10000000, "10M", "10 Mio.", "10 M", "1,000万"),
+        (50000000, "50M", "50 Mio.", "50 M", "5,000万"),
+        (100000000, "100M", "100 Mio.", "100 M", "1.0億"),
+        (1000000000, "1000M", "1.000 Mio.", "1.000 M", "10億"),
+        // Test numbers with billions suffix (above 1500 * 1000^2)
+        (1500000000, "1.5B", "1,5 Mrd.", "1,5 mil M", "15億"),
+        (5000000000, "5.0B", "5,0 Mrd.", "5,0 mil M", "50億"),
+        (10000000000, "10B", "10 Mrd.", "10 mil M", "100億"),
+        (1000000000000, "1000B", "1.000 Mrd.", "1.000 mil M", "1.0兆"),
+        // Test numbers with trillions suffix (above 1500 * 1000^3)
+        (1500000000000, "1.5T", "1,5 Bio.", "1,5 B", "1.5兆"),
+        (10000000000000, "10T", "10 Bio.", "10 B", "10兆"),
+        (999999999999999, "1000T", "1.000 Bio.", "1.000 B", "1,000兆"),
+        // Test that the largest unit is used for values beyond the threshold
+        (
+            1500000000000000,
+            "1500T",
+            "1.500 Bio.",
+            "1.500 B",
+            "1,500兆",
+        ),
+    ];
+
+    #[test]
+    fn test_format_bytes() {
+        for (bytes, english, german, spanish, japanese) in BYTES_CASES {
+            assert_eq!(format_bytes(*bytes, Locale::English), *english);
+            assert_eq!(format_bytes(*bytes, Locale::German), *german);
+            assert_eq!(format_bytes(*bytes, Locale::Spanish), *spanish);
+            assert_eq!(format_bytes(*bytes, Locale::Japanese), *japanese);
+        }
     }
 
     #[test]
     fn test_format_number() {
-        // Test numbers without suffix (below 1500)
-        assert_eq!(format_number(0), "0");
-        assert_eq!(format_number(1), "1");
-        assert_eq!(format_number(1000), "1000");
-        assert_eq!(format_number(1499), "1499");
-
-        // Test numbers with k suffix (1500 to 1500 * 1000)
-        assert_eq!(format_number(1500), "1.5K");
-        assert_eq!(format_number(2000), "2.0K");
-        assert_eq!(format_number(5000), "5.0K");
-        assert_eq!(format_number(10000), "10K");
-        assert_eq!(format_number(50000), "50K");
-        assert_eq!(format_number(100000), "100K");
-        assert_eq!(format_number(500000), "500K");
-        assert_eq!(format_number(999999), "1000K");
-
-        // Test numbers with M suffix (above 1500 * 1000)
-        assert_eq!(format_number(1500000), "1.5M");
-        assert_eq!(format_number(2000000), "2.0M");
-        assert_eq!(format_number(5000000), "5.0M");
-        assert_eq!(format_number(10000000), "10M");
-        assert_eq!(format_number(50000000), "50M");
-        assert_eq!(format_number(100000000), "100M");
-        assert_eq!(format_number(1000000000), "1000M");
+        for (number, english, german, spanish, japanese) in NUMBER_CASES {
+            assert_eq!(format_number(*number, Locale::English), *english);
+            assert_eq!(format_number(*number, Locale::German), *german);
+            assert_eq!(format_number(*number, Locale::Spanish), *spanish);
+            assert_eq!(format_number(*number, Locale::Japanese), *japanese);
+        }
     }
 }
@@ -1,25 +1,67 @@
 #![doc = include_str!("../README.md")]
 
+mod avatar;
+mod branding;
+mod builder;
+#[cfg(feature = "embedded-typst")]
+mod embedded;
 mod env;
 mod error;
 mod formatting;
-
-pub use error::OgImageError;
-
+mod locale;
+mod optimize;
+mod options;
+mod output;
+mod owned;
+mod preset;
+mod renderer;
+mod script;
+mod template;
+mod theme;
+mod transcode;
+
+pub use avatar::AvatarFetchOptions;
+pub use branding::Branding;
+pub use builder::OgImageDataBuilder;
+pub use error::{OgImageError, ValidationError};
+pub use locale::Locale;
+pub use optimize::{PngOptimization, PngOptimizerBackend, PngStripMode};
+pub use options::GenerateOptions;
+pub use output::{OutputFormat, RenderFormat};
+pub use owned::{OgImageAuthorDataOwned, OgImageDataOwned, OgImageDataRef};
+pub use preset::CardPreset;
+#[cfg(feature = "embedded-typst")]
+pub use renderer::TypstEmbeddedRenderer;
+pub use renderer::{
+    DEFAULT_PPI, RenderBackend, RenderFuture, RenderRequest, Renderer, TypstCliRenderer,
+};
+pub use template::Template;
+pub use theme::{Color, Theme};
+
+use crate::avatar::download_avatar;
 use crate::env::var;
-use crate::formatting::{serialize_bytes, serialize_number, serialize_optional_number};
-use reqwest::StatusCode;
+use crate::formatting::{
+    format_bytes, format_number, serialize_bytes, serialize_number, serialize_optional_number,
+};
+use crate::renderer::validate_ppi;
+use crate::script::Script;
+use bytes::Bytes;
+use futures_util::{StreamExt, stream};
 use serde::Serialize;
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 use tempfile::NamedTempFile;
 use tokio::fs;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio::process::Command;
-use tracing::{debug, error, info, instrument, warn};
+use tracing::{debug, info, instrument, warn};
 
 /// Data structure containing information needed to generate an OpenGraph image
 /// for a crates.io crate.
+///
+/// See [`OgImageDataOwned`] for an owned variant that can be deserialized.
 #[derive(Debug, Clone, Serialize)]
 pub struct OgImageData<'a> {
     /// The crate name
@@ -39,10 +81,92 @@ pub struct OgImageData<'a> {
     pub lines_of_code: Option<u32>,
     /// Package size in bytes
     #[serde(serialize_with = "serialize_bytes")]
-    pub crate_size: u32,
+    pub crate_size: u64,
     /// Total number of releases
     #[serde(serialize_with = "serialize_number")]
     pub releases: u32,
+    /// Total number of downloads (optional)
+    #[serde(serialize_with = "serialize_optional_number")]
+    pub downloads: Option<u64>,
+    /// Number of downloads in the last 90 days (optional)
+    #[serde(serialize_with = "serialize_optional_number")]
+    pub recent_downloads: Option<u64>,
+    /// Download counts in chronological order, e.g. 90 daily buckets,
+    /// rendered as a sparkline (empty if not available)
+    pub download_history: &'a [u64],
+}
+
+impl OgImageData<'_> {
+    /// Returns a builder for validated [`OgImageDataOwned`].
+    ///
+    /// The builder trims whitespace, strips control characters and rejects
+    /// empty or overlong values instead of letting the template truncate
+    /// them.
+    pub fn builder(name: impl Into<String>, version: impl Into<String>) -> OgImageDataBuilder {
+        OgImageDataBuilder::new(name.into(), version.into())
+    }
+}
+
+/// The data passed to the template as the `data` input, with all numbers
+/// formatted for the [`Locale`] of the [`GenerateOptions`].
+#[derive(Serialize)]
+struct TemplateData<'a> {
+    name: &'a str,
+    version: &'a str,
+    description: Option<&'a str>,
+    /// Direction and language of the description, so that the template can
+    /// align and truncate right-to-left text
+    description_script: Option<Script>,
+    license: Option<&'a str>,
+    tags: &'a [&'a str],
+    authors: Vec<TemplateAuthor<'a>>,
+    lines_of_code: Option<String>,
+    crate_size: String,
+    releases: String,
+    downloads: Option<String>,
+    recent_downloads: Option<String>,
+    download_history: &'a [u64],
+}
+
+impl<'a> TemplateData<'a> {
+    fn new(data: &'a OgImageData<'a>, locale: Locale) -> Self {
+        let number = |number: u64| format_number(number, locale);
+
+        Self {
+            name: data.name,
+            version: data.version,
+            description: data.description,
+            description_script: data.description.and_then(Script::detect),
+            license: data.license,
+            tags: data.tags,
+            authors: data.authors.iter().map(TemplateAuthor::new).collect(),
+            lines_of_code: data.lines_of_code.map(u64::from).map(number),
+            crate_size: format_bytes(data.crate_size, locale),
+            releases: number(data.releases.into()),
+            downloads: data.downloads.map(number),
+            recent_downloads: data.recent_downloads.map(number),
+            download_history: data.download_history,
+        }
+    }
+}
+
+/// An author as passed to the template as part of the `data` input.
+#[derive(Serialize)]
+struct TemplateAuthor<'a> {
+    #[serde(flatten)]
+    author: &'a OgImageAuthorData<'a>,
+    /// Direction and language of the name, so that the template can render
+    /// right-to-left names correctly
+    script: Option<Script>,
+}
+
+impl<'a> TemplateAuthor<'a> {
+    fn new(author: &'a OgImageAuthorData<'a>) -> Self {
+        Self {
+            author,
+            script: Script::detect(author.name),
+        }
+    }
 }
 
 /// Author information for OpenGraph image generation
@@ -66,17 +190,121 @@ impl<'a> OgImageAuthorData<'a> {
     }
 }
 
+/// An OpenGraph image generated by [`OgImageGenerator::generate()`].
+#[derive(Debug)]
+pub struct OgImage {
+    /// Temporary file containing the generated image
+    pub file: NamedTempFile,
+    /// File format of the generated image
+    pub format: OutputFormat,
+    /// Width of the image in pixels, or in points for vector formats
+    pub width: u32,
+    /// Height of the image in pixels, or in points for vector formats
+    pub height: u32,
+    /// Size statistics of the PNG optimization, if it succeeded
+    pub optimization: Option<PngOptimization>,
+}
+
+impl OgImage {
+    /// Returns the path of the generated image file.
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Returns the MIME type of the generated image, e.g. for a
+    /// `Content-Type` header.
+    pub fn mime_type(&self) -> &'static str {
+        self.format.mime_type()
+    }
+}
+
+/// An OpenGraph image generated in memory by
+/// [`OgImageGenerator::generate_to_bytes()`].
+#[derive(Debug, Clone)]
+pub struct OgImageBytes {
+    /// Contents of the generated image
+    pub bytes: Bytes,
+    /// File format of the generated image
+    pub format: OutputFormat,
+    /// Width of the image in pixels, or in points for vector formats
+    pub width: u32,
+    /// Height of the image in pixels, or in points for vector formats
+    pub height: u32,
+    /// Size statistics of the PNG optimization, if it succeeded
+    pub optimization: Option<PngOptimization>,
+}
+
+impl OgImageBytes {
+    /// Returns the MIME type of the generated image, e.g. for a
+    /// `Content-Type` header.
+    pub fn mime_type(&self) -> &'static str {
+        self.format.mime_type()
+    }
+
+    /// Writes the image into a temporary file with the matching extension.
+    async fn into_temp_file(self) -> Result<OgImage, OgImageError> {
+        let output_file = tempfile::Builder::new()
+            .suffix(&format!(".{}", self.format.extension()))
+            .tempfile()
+            .map_err(OgImageError::TempFileError)?;
+        debug!(
+            output_path = %output_file.path().display(),
+            output_size_bytes = self.bytes.len(),
+            "Writing output file"
+        );
+        fs::write(output_file.path(), &self.bytes).await?;
+
+        Ok(OgImage {
+            file: output_file,
+            format: self.format,
+            width: self.width,
+            height: self.height,
+            optimization: self.optimization,
+        })
+    }
+}
+
 /// Generator for creating OpenGraph images using the Typst typesetting system.
 ///
 /// This struct manages the path to the Typst binary and provides methods for
 /// generating PNG images from a Typst template.
 pub struct OgImageGenerator {
+    backend: RenderBackend,
+    renderer: Option<Arc<dyn Renderer>>,
+    template: Template,
+    branding: Branding,
+    fonts: Vec<Cow<'static, str>>,
+    avatar_concurrency: usize,
+    avatar_fetch_options: AvatarFetchOptions,
+    http_client: OnceLock<reqwest::Client>,
     typst_binary_path: PathBuf,
     typst_font_path: Option<PathBuf>,
+    ppi: f32,
     oxipng_binary_path: PathBuf,
+    png_optimizer: PngOptimizerBackend,
+    png_optimization_level: u8,
+    png_strip_mode: PngStripMode,
+    #[cfg(feature = "embedded-typst")]
+    font_cache: Arc<embedded::FontCache>,
 }
 
 impl OgImageGenerator {
+    /// The default font fallback chain of the card text.
+    ///
+    /// Fira Sans is used for all characters it covers, while the Noto fonts
+    /// cover Chinese, Japanese and Korean text, emoji and other symbols if
+    /// they are installed or available in the font path.
+    pub const DEFAULT_FONTS: &'static [&'static str] = &[
+        "Fira Sans",
+        "Noto Sans CJK JP",
+        "Noto Color Emoji",
+        "Noto Sans Symbols",
+        "Noto Sans Symbols 2",
+    ];
+
+    /// The default number of avatars that are downloaded at the same time.
+    pub const DEFAULT_AVATAR_CONCURRENCY: usize = 4;
+
     /// Creates a new `OgImageGenerator` with default binary paths.
     ///
     /// Uses "typst" and "oxipng" as default binary paths, assuming they are
@@ -160,6 +388,81 @@ impl OgImageGenerator {
         Ok(generator)
     }
 
+    /// Sets the built-in backend used to compile the Typst template.
+    ///
+    /// Defaults to `RenderBackend::Embedded` if the `embedded-typst`
+    /// feature is enabled, and to [`RenderBackend::Cli`] otherwise. This
+    /// setting is ignored if a custom renderer has been set via
+    /// [`with_renderer()`](Self::with_renderer).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crates_io_og_image::{OgImageGenerator, RenderBackend};
+    ///
+    /// let generator = OgImageGenerator::default().with_backend(RenderBackend::Cli);
+    /// ```
+    pub fn with_backend(mut self, backend: RenderBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Sets a custom [`Renderer`] for the generator.
+    ///
+    /// This replaces the built-in Typst backends, e.g. to render images on a
+    /// remote worker, or to use a fake renderer in tests. The Typst binary
+    /// and font paths are not used by custom renderers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use crates_io_og_image::{OgImageGenerator, TypstCliRenderer};
+    ///
+    /// let renderer = TypstCliRenderer::new(PathBuf::from("/usr/local/bin/typst"), None);
+    /// let generator = OgImageGenerator::default().with_renderer(renderer);
+    /// ```
+    pub fn with_renderer(mut self, renderer: impl Renderer + 'static) -> Self {
+        self.renderer = Some(Arc::new(renderer));
+        self
+    }
+
+    /// Sets the [`Template`] used to render the images, instead of the
+    /// template bundled with the crate.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use crates_io_og_image::{OgImageError, OgImageGenerator, Template};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), OgImageError> {
+    /// let template = Template::from_dir("my-template").await?;
+    /// let generator = OgImageGenerator::default().with_template(template);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_template(mut self, template: Template) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Sets the registry name, header logo and watermark shown on the cards,
+    /// instead of the crates.io branding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crates_io_og_image::{Branding, OgImageGenerator};
+    ///
+    /// let branding = Branding::new("registry.example.com");
+    /// let generator = OgImageGenerator::default().with_branding(branding);
+    /// ```
+    pub fn with_branding(mut self, branding: Branding) -> Self {
+        self.branding = branding;
+        self
+    }
+
     /// Sets the Typst binary path for the generator.
     ///
     /// This allows specifying a custom path to the Typst binary.
@@ -200,6 +503,160 @@ impl OgImageGenerator {
         self
     }
 
+    /// Sets the maximum number of avatars that are downloaded at the same
+    /// time.
+    ///
+    /// Defaults to [`DEFAULT_AVATAR_CONCURRENCY`](Self::DEFAULT_AVATAR_CONCURRENCY).
+    /// A limit of `0` is treated as `1`, i.e. sequential downloads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crates_io_og_image::OgImageGenerator;
+    ///
+    /// let generator = OgImageGenerator::default().with_avatar_concurrency(8);
+    /// ```
+    pub fn with_avatar_concurrency(mut self, concurrency: usize) -> Self {
+        self.avatar_concurrency = concurrency;
+        self
+    }
+
+    /// Sets the timeouts, the size limit and the retries of the avatar
+    /// downloads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use crates_io_og_image::{AvatarFetchOptions, OgImageGenerator};
+    ///
+    /// let options = AvatarFetchOptions::default().with_connect_timeout(Duration::from_secs(2));
+    /// let generator = OgImageGenerator::default().with_avatar_fetch_options(options);
+    /// ```
+    pub fn with_avatar_fetch_options(mut self, options: AvatarFetchOptions) -> Self {
+        self.avatar_fetch_options = options;
+        // The timeouts are configured on the client, so it has to be recreated
+        self.http_client = OnceLock::new();
+        self
+    }
+
+    /// Sets the ordered font fallback chain of the card text, instead of
+    /// [`DEFAULT_FONTS`](Self::DEFAULT_FONTS).
+    ///
+    /// Every character is rendered with the first font in the list that
+    /// covers it. Fonts that are not available are skipped, so the list can
+    /// contain fonts that are only installed on some hosts. The fonts are
+    /// passed to the template as the `fonts` input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use crates_io_og_image::OgImageGenerator;
+    ///
+    /// let generator = OgImageGenerator::default()
+    ///     .with_font_path(PathBuf::from("/usr/share/fonts"))
+    ///     .with_fonts(["Fira Sans", "Noto Sans CJK SC", "Noto Emoji"]);
+    /// ```
+    pub fn with_fonts<I>(mut self, fonts: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Cow<'static, str>>,
+    {
+        self.fonts = fonts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the characters of the card that none of the configured fonts
+    /// can render, e.g. to log crates whose cards would contain placeholder
+    /// glyphs.
+    ///
+    /// This includes the `data` with its numbers formatted for the locale of
+    /// the `options`, the translated labels and the registry name of the
+    /// [branding](Self::with_branding).
+    ///
+    /// The fonts are discovered like the embedded renderer discovers them,
+    /// i.e. from the [font path](Self::with_font_path) if it is set, and
+    /// from the system fonts otherwise. Whitespace and invisible formatting
+    /// characters are ignored.
+    ///
+    /// Only available with the `embedded-typst` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use crates_io_og_image::{GenerateOptions, OgImageData, OgImageError, OgImageGenerator};
+    ///
+    /// # async fn check(data: OgImageData<'_>) -> Result<(), OgImageError> {
+    /// let generator = OgImageGenerator::from_environment()?;
+    ///
+    /// let options = GenerateOptions::default();
+    /// let missing = generator.unsupported_chars(&data, &options).await?;
+    /// if !missing.is_empty() {
+    ///     eprintln!("{} contains unsupported characters: {missing:?}", data.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "embedded-typst")]
+    pub async fn unsupported_chars(
+        &self,
+        data: &OgImageData<'_>,
+        options: &GenerateOptions,
+    ) -> Result<std::collections::BTreeSet<char>, OgImageError> {
+        let data = TemplateData::new(data, options.locale());
+        let labels = options.locale().label_texts();
+
+        let texts = [data.name, data.version, self.branding.name()]
+            .into_iter()
+            .chain(data.description)
+            .chain(data.license)
+            .chain(data.tags.iter().copied())
+            .chain(data.authors.iter().map(|author| author.author.name))
+            .chain(data.lines_of_code.as_deref())
+            .chain([data.crate_size.as_str(), data.releases.as_str()])
+            .chain(data.downloads.as_deref())
+            .chain(data.recent_downloads.as_deref())
+            .chain(labels.iter().map(String::as_str));
+
+        let chars = texts.flat_map(str::chars).collect::<Vec<_>>();
+
+        let font_path = self.typst_font_path.clone();
+        let font_cache = self.font_cache.clone();
+        let fonts = self.fonts.clone();
+
+        // Font discovery reads all font files on first use, so it runs on the
+        // blocking thread pool like the compilation itself
+        tokio::task::spawn_blocking(move || {
+            font_cache.missing_chars(font_path.as_deref(), &fonts, chars)
+        })
+        .await
+        .map_err(OgImageError::RenderTaskError)
+    }
+
+    /// Sets the pixel density of raster images in pixels per inch.
+    ///
+    /// With the default [`CardPreset::OpenGraph`], the default of 144 PPI
+    /// produces 1200×630 pixel images, while 288 PPI produces 2400×1260 pixel
+    /// images. Vector formats are not affected by this setting.
+    ///
+    /// Returns an error if the pixel density is not a finite positive number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crates_io_og_image::{OgImageError, OgImageGenerator};
+    ///
+    /// # fn main() -> Result<(), OgImageError> {
+    /// let generator = OgImageGenerator::default().with_ppi(288.)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_ppi(mut self, ppi: f32) -> Result<Self, OgImageError> {
+        self.ppi = validate_ppi(ppi)?;
+        Ok(self)
+    }
+
     /// Sets the oxipng binary path for PNG optimization.
     ///
     /// This allows specifying a custom path to the oxipng binary for PNG optimization.
@@ -219,113 +676,130 @@ impl OgImageGenerator {
         self
     }
 
-    /// Processes avatars by downloading URLs and copying assets to the assets directory.
+    /// Sets the backend used for PNG optimization.
+    ///
+    /// Defaults to `PngOptimizerBackend::Embedded` if the `embedded-oxipng`
+    /// feature is enabled, and to [`PngOptimizerBackend::Cli`] otherwise.
+    ///
+    /// # Examples
     ///
-    /// This method handles both asset-based avatars (which are copied from the bundled assets)
-    /// and URL-based avatars (which are downloaded from the internet).
+    /// ```
+    /// use crates_io_og_image::{OgImageGenerator, PngOptimizerBackend};
+    ///
+    /// let generator = OgImageGenerator::default()
+    ///     .with_png_optimizer(PngOptimizerBackend::Cli);
+    /// ```
+    pub fn with_png_optimizer(mut self, backend: PngOptimizerBackend) -> Self {
+        self.png_optimizer = backend;
+        self
+    }
+
+    /// Sets the oxipng optimization level for PNG optimization.
+    ///
+    /// Levels range from 0 (fastest) to 6 (smallest output). Values above 6
+    /// are clamped. Defaults to 2, which is a good balance between speed and
+    /// compression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crates_io_og_image::OgImageGenerator;
+    ///
+    /// let generator = OgImageGenerator::default().with_png_optimization_level(4);
+    /// ```
+    pub fn with_png_optimization_level(mut self, level: u8) -> Self {
+        self.png_optimization_level = level.min(6);
+        self
+    }
+
+    /// Sets which metadata chunks are removed during PNG optimization.
+    ///
+    /// Defaults to [`PngStripMode::Safe`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crates_io_og_image::{OgImageGenerator, PngStripMode};
+    ///
+    /// let generator = OgImageGenerator::default().with_png_strip_mode(PngStripMode::All);
+    /// ```
+    pub fn with_png_strip_mode(mut self, strip_mode: PngStripMode) -> Self {
+        self.png_strip_mode = strip_mode;
+        self
+    }
+
+    /// Returns the HTTP client for the avatar downloads, creating it on
+    /// first use, so that connections are reused between images.
+    fn http_client(&self) -> Result<&reqwest::Client, OgImageError> {
+        if let Some(client) = self.http_client.get() {
+            return Ok(client);
+        }
+
+        let client = self.avatar_fetch_options.build_client()?;
+        Ok(self.http_client.get_or_init(|| client))
+    }
+
+    /// Processes avatars by downloading them from their URLs.
+    ///
+    /// Up to [`avatar_concurrency`](Self::with_avatar_concurrency) avatars
+    /// are downloaded at the same time. The downloaded avatars are added to
+    /// `assets` as `assets/avatar_{index}.{ext}`, so that they can be read by
+    /// the Typst template.
     /// Returns a mapping from avatar source to the local filename.
-    #[instrument(skip(self, data), fields(krate.name = %data.name))]
+    #[instrument(skip(self, data, assets), fields(krate.name = %data.name))]
     async fn process_avatars<'a>(
         &self,
         data: &'a OgImageData<'_>,
-        assets_dir: &Path,
+        assets: &mut BTreeMap<String, Cow<'static, [u8]>>,
     ) -> Result<HashMap<&'a str, String>, OgImageError> {
         let mut avatar_map = HashMap::new();
 
-        let client = reqwest::Client::new();
-        for (index, author) in data.authors.iter().enumerate() {
-            if let Some(avatar) = &author.avatar {
+        let client = self.http_client()?;
+        let options = &self.avatar_fetch_options;
+        let downloads = data
+            .authors
+            .iter()
+            .enumerate()
+            .filter_map(|(index, author)| {
+                let avatar = author.avatar.as_deref()?;
                 debug!(
                     author_name = %author.name,
                     avatar_url = %avatar,
                     "Processing avatar for author {}", author.name
                 );
 
-                // Download the avatar from the URL
-                debug!(url = %avatar, "Downloading avatar from URL: {avatar}");
-                let response = client.get(avatar.as_ref()).send().await.map_err(|err| {
-                    OgImageError::AvatarDownloadError {
-                        url: avatar.to_string(),
-                        source: err,
-                    }
-                })?;
-
-                let status = response.status();
-                if status == StatusCode::NOT_FOUND {
-                    warn!(url = %avatar, "Avatar URL returned 404 Not Found");
-                    continue; // Skip this avatar if not found
-                }
-
-                if let Err(err) = response.error_for_status_ref() {
-                    return Err(OgImageError::AvatarDownloadError {
-                        url: avatar.to_string(),
-                        source: err,
-                    });
-                }
-
-                let content_length = response.content_length();
-                debug!(
-                    url = %avatar,
-                    content_length = ?content_length,
-                    status = %response.status(),
-                    "Avatar download response received"
-                );
-
-                let bytes = response.bytes().await;
-                let bytes = bytes.map_err(|err| {
-                    error!(url = %avatar, error = %err, "Failed to read avatar response bytes");
-                    OgImageError::AvatarDownloadError {
-                        url: (*avatar).to_string(),
-                        source: err,
-                    }
-                })?;
-
-                debug!(url = %avatar, size_bytes = bytes.len(), "Avatar downloaded successfully");
-
-                // Detect the image format and determine the appropriate file extension
-                let Some(extension) = Self::detect_image_format(&bytes) else {
-                    // Format not supported, log warning with first 20 bytes for debugging
-                    let debug_bytes = &bytes[..bytes.len().min(20)];
-                    let hex_bytes = debug_bytes
-                        .iter()
-                        .map(|b| format!("{b:02x}"))
-                        .collect::<Vec<_>>()
-                        .join(" ");
-
-                    warn!("Unsupported avatar format at {avatar}, first 20 bytes: {hex_bytes}");
-
-                    // Skip this avatar and continue with the next one
-                    continue;
-                };
-
-                let filename = format!("avatar_{index}.{extension}");
-                let avatar_path = assets_dir.join(&filename);
-
-                debug!(
-                    author_name = %author.name,
-                    avatar_url = %avatar,
-                    avatar_path = %avatar_path.display(),
-                    "Writing avatar file with detected format"
-                );
+                Some(async move {
+                    (
+                        index,
+                        avatar,
+                        download_avatar(client, avatar, options).await,
+                    )
+                })
+            });
 
-                // Write the bytes to the avatar file
-                fs::write(&avatar_path, &bytes).await.map_err(|err| {
-                    OgImageError::AvatarWriteError {
-                        path: avatar_path.clone(),
-                        source: err,
-                    }
-                })?;
+        // Download the avatars concurrently, but process them in the order of
+        // the authors, so that the first error is returned like before
+        let concurrency = self.avatar_concurrency.max(1);
+        let mut downloads = stream::iter(downloads).buffered(concurrency);
+        while let Some((index, avatar, result)) = downloads.next().await {
+            let Some((extension, bytes)) = result? else {
+                continue;
+            };
+
+            let filename = format!("avatar_{index}.{extension}");
+
+            debug!(
+                avatar_url = %avatar,
+                filename = %filename,
+                size_bytes = bytes.len(),
+                "Avatar processed successfully"
+            );
 
-                debug!(
-                    author_name = %author.name,
-                    path = %avatar_path.display(),
-                    size_bytes = bytes.len(),
-                    "Avatar processed and written successfully"
-                );
+            // Store the avatar alongside the other assets
+            assets.insert(format!("assets/{filename}"), Cow::Owned(bytes.into()));
 
-                // Store the mapping from the avatar source to the numbered filename
-                avatar_map.insert(avatar.as_ref(), filename);
-            }
+            // Store the mapping from the avatar source to the numbered filename
+            avatar_map.insert(avatar, filename);
         }
 
         Ok(avatar_map)
@@ -333,9 +807,9 @@ impl OgImageGenerator {
 
     /// Generates an OpenGraph image using the provided data.
     ///
-    /// This method creates a temporary directory with all the necessary files
-    /// to create the OpenGraph image, compiles it to PNG using the Typst
-    /// binary, and returns the resulting image as a `NamedTempFile`.
+    /// This method collects all the assets necessary to create the OpenGraph
+    /// image, renders it to PNG using the configured [`Renderer`], optimizes
+    /// it, and returns the resulting image as an [`OgImage`].
     ///
     /// # Examples
     ///
@@ -355,53 +829,303 @@ impl OgImageGenerator {
     ///     lines_of_code: Some(5000),
     ///     crate_size: 100,
     ///     releases: 10,
+    ///     downloads: None,
+    ///     recent_downloads: None,
+    ///     download_history: &[],
+    /// };
+    /// let image = generator.generate(data).await?;
+    /// println!("Generated image at: {:?}", image.path());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate(&self, data: OgImageData<'_>) -> Result<OgImage, OgImageError> {
+        let options = GenerateOptions::default();
+        self.generate_with_options(data, &options).await
+    }
+
+    /// Generates an OpenGraph image using the provided data and options.
+    ///
+    /// This works like [`generate()`](Self::generate), but allows choosing
+    /// e.g. the [`OutputFormat`] of the image. PNG optimization is only
+    /// applied to [`OutputFormat::Png`] images, while WebP, JPEG and AVIF
+    /// images are transcoded from the unoptimized PNG.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use crates_io_og_image::{GenerateOptions, OgImageData, OgImageError, OgImageGenerator, OutputFormat};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), OgImageError> {
+    /// let generator = OgImageGenerator::default();
+    /// let data = OgImageData {
+    ///     name: "my-crate",
+    ///     version: "1.0.0",
+    ///     description: None,
+    ///     license: None,
+    ///     tags: &[],
+    ///     authors: &[],
+    ///     lines_of_code: None,
+    ///     crate_size: 100,
+    ///     releases: 10,
+    ///     downloads: None,
+    ///     recent_downloads: None,
+    ///     download_history: &[],
+    /// };
+    /// let options = GenerateOptions::default().with_format(OutputFormat::Svg);
+    /// let image = generator.generate_with_options(data, &options).await?;
+    /// println!("Generated SVG at: {:?}", image.path());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(skip(self, data, options), fields(
+        crate.name = %data.name,
+        crate.version = %data.version,
+        author_count = data.authors.len(),
+        format = ?options.format(),
+    ))]
+    pub async fn generate_with_options(
+        &self,
+        data: OgImageData<'_>,
+        options: &GenerateOptions,
+    ) -> Result<OgImage, OgImageError> {
+        let image = self.generate_to_bytes(data, options).await?;
+        image.into_temp_file().await
+    }
+
+    /// Generates an OpenGraph image in memory using the provided data and
+    /// options.
+    ///
+    /// This works like [`generate_with_options()`](Self::generate_with_options),
+    /// but returns the image contents together with its format and
+    /// dimensions, without writing them to the filesystem.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use crates_io_og_image::{GenerateOptions, OgImageData, OgImageError, OgImageGenerator};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), OgImageError> {
+    /// let generator = OgImageGenerator::default();
+    /// let data = OgImageData {
+    ///     name: "my-crate",
+    ///     version: "1.0.0",
+    ///     description: None,
+    ///     license: None,
+    ///     tags: &[],
+    ///     authors: &[],
+    ///     lines_of_code: None,
+    ///     crate_size: 100,
+    ///     releases: 10,
+    ///     downloads: None,
+    ///     recent_downloads: None,
+    ///     download_history: &[],
     /// };
-    /// let image_file = generator.generate(data).await?;
-    /// println!("Generated image at: {:?}", image_file.path());
+    /// let image = generator.generate_to_bytes(data, &GenerateOptions::default()).await?;
+    /// println!("Generated {}x{} {} image", image.width, image.height, image.mime_type());
     /// # Ok(())
     /// # }
     /// ```
-    #[instrument(skip(self, data), fields(
+    #[instrument(skip(self, data, options), fields(
         crate.name = %data.name,
         crate.version = %data.version,
         author_count = data.authors.len(),
+        format = ?options.format(),
     ))]
-    pub async fn generate(&self, data: OgImageData<'_>) -> Result<NamedTempFile, OgImageError> {
+    pub async fn generate_to_bytes(
+        &self,
+        data: OgImageData<'_>,
+        options: &GenerateOptions,
+    ) -> Result<OgImageBytes, OgImageError> {
         let start_time = std::time::Instant::now();
         info!("Starting OpenGraph image generation");
 
-        // Create a temporary folder
-        let temp_dir = tempfile::tempdir().map_err(OgImageError::TempDirError)?;
-        debug!(temp_dir = %temp_dir.path().display(), "Created temporary directory");
-
-        // Create assets directory and copy logo and icons
-        let assets_dir = temp_dir.path().join("assets");
-        debug!(assets_dir = %assets_dir.display(), "Creating assets directory");
-        fs::create_dir(&assets_dir).await?;
-
-        debug!("Copying bundled assets to temporary directory");
-        let cargo_logo = include_bytes!("../template/assets/cargo.png");
-        fs::write(assets_dir.join("cargo.png"), cargo_logo).await?;
-        let rust_logo_svg = include_bytes!("../template/assets/rust-logo.svg");
-        fs::write(assets_dir.join("rust-logo.svg"), rust_logo_svg).await?;
-
-        // Copy SVG icons
-        debug!("Copying SVG icon assets");
-        let code_branch_svg = include_bytes!("../template/assets/code-branch.svg");
-        fs::write(assets_dir.join("code-branch.svg"), code_branch_svg).await?;
-        let code_svg = include_bytes!("../template/assets/code.svg");
-        fs::write(assets_dir.join("code.svg"), code_svg).await?;
-        let scale_balanced_svg = include_bytes!("../template/assets/scale-balanced.svg");
-        fs::write(assets_dir.join("scale-balanced.svg"), scale_balanced_svg).await?;
-        let tag_svg = include_bytes!("../template/assets/tag.svg");
-        fs::write(assets_dir.join("tag.svg"), tag_svg).await?;
-        let weight_hanging_svg = include_bytes!("../template/assets/weight-hanging.svg");
-        fs::write(assets_dir.join("weight-hanging.svg"), weight_hanging_svg).await?;
-
-        // Process avatars - download URLs and copy assets
+        let request = self.prepare_request(&data, options).await?;
+
+        let compilation_start_time = std::time::Instant::now();
+        let renderer = self.renderer();
+        let image = renderer.render(request).await?;
+        let compilation_duration = compilation_start_time.elapsed();
+
+        debug!(
+            duration_ms = compilation_duration.as_millis(),
+            output_size_bytes = image.len(),
+            "Rendering completed successfully"
+        );
+
+        let image = self.finish_image(image, options, self.ppi).await?;
+
+        let duration = start_time.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            output_size_bytes = image.bytes.len(),
+            "OpenGraph image generation completed successfully"
+        );
+
+        Ok(image)
+    }
+
+    /// Generates an OpenGraph image and writes it into the given `writer`.
+    ///
+    /// This is a convenience wrapper around
+    /// [`generate_to_bytes()`](Self::generate_to_bytes): the image is
+    /// optimized and transcoded in memory, so the whole image is buffered
+    /// before it is written. The writer is flushed, but not shut down, after
+    /// the image was written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use crates_io_og_image::{GenerateOptions, OgImageData, OgImageError, OgImageGenerator};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), OgImageError> {
+    /// let generator = OgImageGenerator::default();
+    /// let data = OgImageData {
+    ///     name: "my-crate",
+    ///     version: "1.0.0",
+    ///     description: None,
+    ///     license: None,
+    ///     tags: &[],
+    ///     authors: &[],
+    ///     lines_of_code: None,
+    ///     crate_size: 100,
+    ///     releases: 10,
+    ///     downloads: None,
+    ///     recent_downloads: None,
+    ///     download_history: &[],
+    /// };
+    /// let mut file = tokio::fs::File::create("og-image.png").await?;
+    /// let options = GenerateOptions::default();
+    /// generator.generate_into(data, &options, &mut file).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_into<W>(
+        &self,
+        data: OgImageData<'_>,
+        options: &GenerateOptions,
+        writer: &mut W,
+    ) -> Result<(), OgImageError>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let image = self.generate_to_bytes(data, options).await?;
+
+        debug!(output_size_bytes = image.bytes.len(), "Writing output");
+        writer
+            .write_all(&image.bytes)
+            .await
+            .map_err(OgImageError::OutputWriteError)?;
+        writer.flush().await.map_err(OgImageError::OutputWriteError)
+    }
+
+    /// Generates the same OpenGraph image at multiple pixel densities.
+    ///
+    /// This works like [`generate_with_options()`](Self::generate_with_options),
+    /// but renders the image once for every entry in `ppis`. The avatars are
+    /// only downloaded once, and the built-in renderers share their setup
+    /// between the renders. The images are returned in the order of `ppis`.
+    ///
+    /// With the default [`CardPreset::OpenGraph`], `&[144., 288.]` produces a
+    /// 1200×630 and a 2400×1260 pixel image. The densities only affect raster
+    /// formats. Returns an error if any density is not a finite positive
+    /// number.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use crates_io_og_image::{GenerateOptions, OgImageData, OgImageError, OgImageGenerator};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), OgImageError> {
+    /// let generator = OgImageGenerator::default();
+    /// let data = OgImageData {
+    ///     name: "my-crate",
+    ///     version: "1.0.0",
+    ///     description: None,
+    ///     license: None,
+    ///     tags: &[],
+    ///     authors: &[],
+    ///     lines_of_code: None,
+    ///     crate_size: 100,
+    ///     releases: 10,
+    ///     downloads: None,
+    ///     recent_downloads: None,
+    ///     download_history: &[],
+    /// };
+    /// let options = GenerateOptions::default();
+    /// let images = generator.generate_densities(data, &options, &[144., 288.]).await?;
+    /// assert_eq!(images.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(skip(self, data, options), fields(
+        crate.name = %data.name,
+        crate.version = %data.version,
+        author_count = data.authors.len(),
+        format = ?options.format(),
+    ))]
+    pub async fn generate_densities(
+        &self,
+        data: OgImageData<'_>,
+        options: &GenerateOptions,
+        ppis: &[f32],
+    ) -> Result<Vec<OgImage>, OgImageError> {
+        let start_time = std::time::Instant::now();
+        info!(densities = ?ppis, "Starting OpenGraph image generation");
+
+        for &ppi in ppis {
+            validate_ppi(ppi)?;
+        }
+
+        let request = self.prepare_request(&data, options).await?;
+
+        let compilation_start_time = std::time::Instant::now();
+        let renderer = self.renderer();
+        let rendered = renderer.render_densities(request, ppis.to_vec()).await?;
+        let compilation_duration = compilation_start_time.elapsed();
+
+        debug!(
+            duration_ms = compilation_duration.as_millis(),
+            image_count = rendered.len(),
+            "Rendering completed successfully"
+        );
+
+        let mut images = Vec::with_capacity(rendered.len());
+        for (image, ppi) in rendered.into_iter().zip(ppis) {
+            let image = self.finish_image(image, options, *ppi).await?;
+            images.push(image.into_temp_file().await?);
+        }
+
+        let duration = start_time.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            "OpenGraph image generation completed successfully"
+        );
+
+        Ok(images)
+    }
+
+    /// Collects the assets, downloads the avatars and serializes the data
+    /// into a [`RenderRequest`].
+    async fn prepare_request(
+        &self,
+        data: &OgImageData<'_>,
+        options: &GenerateOptions,
+    ) -> Result<RenderRequest, OgImageError> {
+        options.theme().validate()?;
+
+        // Collect the template and the assets referenced by it
+        debug!("Collecting template assets");
+        let (template, mut assets) = self.template.to_parts();
+        let branding = self.branding.to_input(&mut assets)?;
+
+        // Process avatars - download URLs and add them to the assets
         let avatar_start_time = std::time::Instant::now();
         info!("Processing avatars");
-        let avatar_map = self.process_avatars(&data, &assets_dir).await?;
+        let avatar_map = self.process_avatars(data, &mut assets).await?;
         let avatar_duration = avatar_start_time.elapsed();
         info!(
             avatar_count = avatar_map.len(),
@@ -409,123 +1133,165 @@ impl OgImageGenerator {
             "Avatar processing completed"
         );
 
-        // Copy the static Typst template file
-        let template_content = include_str!("../template/og-image.typ");
-        let typ_file_path = temp_dir.path().join("og-image.typ");
-        debug!(template_path = %typ_file_path.display(), "Copying Typst template");
-        fs::write(&typ_file_path, template_content).await?;
-
-        // Create a named temp file for the output PNG
-        let output_file = NamedTempFile::new().map_err(OgImageError::TempFileError)?;
-        debug!(output_path = %output_file.path().display(), "Created output file");
-
         // Serialize data and avatar_map to JSON
         debug!("Serializing data and avatar map to JSON");
+        let data = TemplateData::new(data, options.locale());
         let json_data =
             serde_json::to_string(&data).map_err(OgImageError::JsonSerializationError)?;
 
         let json_avatar_map =
             serde_json::to_string(&avatar_map).map_err(OgImageError::JsonSerializationError)?;
 
-        // Run typst compile command with input data
-        info!("Running Typst compilation command");
-        let mut command = Command::new(&self.typst_binary_path);
-        command.arg("compile").arg("--format").arg("png");
-
-        // Pass in the data and avatar map as JSON inputs
-        let input = format!("data={json_data}");
-        command.arg("--input").arg(input);
-        let input = format!("avatar_map={json_avatar_map}");
-        command.arg("--input").arg(input);
-
-        // Pass in the font path if specified
-        if let Some(font_path) = &self.typst_font_path {
-            debug!(font_path = %font_path.display(), "Using custom font path");
-            command.arg("--font-path").arg(font_path);
-            command.arg("--ignore-system-fonts");
-        } else {
-            debug!("Using system font discovery");
-        }
+        let json_fonts =
+            serde_json::to_string(&self.fonts).map_err(OgImageError::JsonSerializationError)?;
+
+        let inputs = BTreeMap::from([
+            ("data", json_data),
+            ("avatar_map", json_avatar_map),
+            ("preset", options.preset().to_input()),
+            ("theme", options.theme().to_input()?),
+            ("branding", branding),
+            ("labels", options.locale().to_input()?),
+            ("fonts", json_fonts),
+        ]);
+
+        Ok(RenderRequest {
+            template,
+            inputs,
+            assets,
+            format: options.format().render_format(),
+            ppi: self.ppi,
+        })
+    }
 
-        // Pass input and output file paths
-        command.arg(&typ_file_path).arg(output_file.path());
+    /// Optimizes or transcodes a rendered image into the output format.
+    async fn finish_image(
+        &self,
+        image: Vec<u8>,
+        options: &GenerateOptions,
+        ppi: f32,
+    ) -> Result<OgImageBytes, OgImageError> {
+        let format = options.format();
+
+        // After successful Typst compilation, optimize or transcode the PNG
+        let (image, optimization) = match format {
+            OutputFormat::Png => self.optimize_png(image).await,
+            OutputFormat::Svg | OutputFormat::Pdf => (image, None),
+            OutputFormat::WebP { .. } | OutputFormat::Jpeg { .. } | OutputFormat::Avif { .. } => {
+                debug!("Transcoding rendered PNG");
+                (transcode::transcode(image, format).await?, None)
+            }
+        };
 
-        // Clear environment variables to avoid leaking sensitive data
-        command.env_clear();
+        // Vector formats are measured in points, which are pixels at 72 PPI
+        let ppi = match format.render_format() {
+            RenderFormat::Png => ppi,
+            RenderFormat::Svg | RenderFormat::Pdf => 72.,
+        };
+        let (width, height) = options.preset().pixel_size(ppi);
+
+        Ok(OgImageBytes {
+            bytes: Bytes::from(image),
+            format,
+            width,
+            height,
+            optimization,
+        })
+    }
 
-        // Preserve environment variables needed for font discovery
-        if let Ok(path) = std::env::var("PATH") {
-            command.env("PATH", path);
+    /// Returns the custom renderer, or the built-in renderer of the
+    /// configured [`RenderBackend`].
+    fn renderer(&self) -> Arc<dyn Renderer> {
+        if let Some(renderer) = &self.renderer {
+            return renderer.clone();
         }
-        if let Ok(home) = std::env::var("HOME") {
-            command.env("HOME", home);
-        }
-
-        let compilation_start_time = std::time::Instant::now();
-        let output = command.output().await;
-        let output = output.map_err(OgImageError::TypstNotFound)?;
-        let compilation_duration = compilation_start_time.elapsed();
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            error!(
-                exit_code = ?output.status.code(),
-                stderr = %stderr,
-                stdout = %stdout,
-                duration_ms = compilation_duration.as_millis(),
-                "Typst compilation failed"
-            );
-            return Err(OgImageError::TypstCompilationError {
-                stderr,
-                stdout,
-                exit_code: output.status.code(),
-            });
+        let font_path = self.typst_font_path.clone();
+        match self.backend {
+            RenderBackend::Cli => {
+                let binary_path = self.typst_binary_path.clone();
+                Arc::new(TypstCliRenderer::new(binary_path, font_path))
+            }
+            #[cfg(feature = "embedded-typst")]
+            RenderBackend::Embedded => {
+                let font_cache = self.font_cache.clone();
+                Arc::new(TypstEmbeddedRenderer::with_font_cache(
+                    font_path, font_cache,
+                ))
+            }
         }
-
-        let output_size_bytes = fs::metadata(output_file.path()).await;
-        let output_size_bytes = output_size_bytes.map(|m| m.len()).unwrap_or(0);
-
-        debug!(
-            duration_ms = compilation_duration.as_millis(),
-            output_size_bytes, "Typst compilation completed successfully"
-        );
-
-        // After successful Typst compilation, optimize the PNG
-        self.optimize_png(output_file.path()).await;
-
-        let duration = start_time.elapsed();
-        info!(
-            duration_ms = duration.as_millis(),
-            output_size_bytes, "OpenGraph image generation completed successfully"
-        );
-        Ok(output_file)
     }
 
-    /// Optimizes a PNG file using oxipng.
+    /// Optimizes a PNG image using the configured [`PngOptimizerBackend`].
     ///
     /// This method attempts to reduce the file size of a PNG using lossless compression.
     /// All errors are handled internally and logged as warnings. The method never fails
-    /// to ensure PNG optimization is truly optional.
-    async fn optimize_png(&self, png_file: &Path) {
+    /// to ensure PNG optimization is truly optional. If the optimization fails, the
+    /// original image is returned without [`PngOptimization`] statistics.
+    async fn optimize_png(&self, png: Vec<u8>) -> (Vec<u8>, Option<PngOptimization>) {
         debug!(
-            input_file = %png_file.display(),
-            oxipng_path = %self.oxipng_binary_path.display(),
+            backend = ?self.png_optimizer,
+            level = self.png_optimization_level,
+            strip = ?self.png_strip_mode,
             "Starting PNG optimization"
         );
 
         let start_time = std::time::Instant::now();
 
+        let optimized = match self.png_optimizer {
+            PngOptimizerBackend::Cli => self.optimize_png_with_cli(&png).await,
+            #[cfg(feature = "embedded-oxipng")]
+            PngOptimizerBackend::Embedded => self.optimize_png_embedded(&png).await,
+        };
+
+        let Some(optimized) = optimized else {
+            return (png, None);
+        };
+
+        let optimization = PngOptimization {
+            original_size: png.len(),
+            optimized_size: optimized.len(),
+        };
+
+        debug!(
+            duration_ms = start_time.elapsed().as_millis(),
+            original_size = optimization.original_size,
+            optimized_size = optimization.optimized_size,
+            "PNG optimization completed successfully"
+        );
+
+        (optimized, Some(optimization))
+    }
+
+    /// Optimizes a PNG image by running the oxipng CLI on a temporary file.
+    async fn optimize_png_with_cli(&self, png: &[u8]) -> Option<Vec<u8>> {
+        let png_file = match NamedTempFile::new() {
+            Ok(png_file) => png_file,
+            Err(err) => {
+                warn!(error = %err, "Failed to create temporary file for PNG optimization");
+                return None;
+            }
+        };
+
+        if let Err(err) = fs::write(png_file.path(), png).await {
+            warn!(error = %err, "Failed to write temporary file for PNG optimization");
+            return None;
+        }
+
         let mut command = Command::new(&self.oxipng_binary_path);
 
-        // Default optimization level for speed/compression balance
-        command.arg("--opt").arg("2");
+        // Optimization level for speed/compression balance
+        command
+            .arg("--opt")
+            .arg(self.png_optimization_level.to_string());
 
-        // Remove safe-to-remove metadata
-        command.arg("--strip").arg("safe");
+        // Remove metadata according to the configured strip mode
+        if let Some(strip) = self.png_strip_mode.as_cli_arg() {
+            command.arg("--strip").arg(strip);
+        }
 
         // Overwrite the input PNG file
-        command.arg(png_file);
+        command.arg(png_file.path());
 
         // Clear environment variables to avoid leaking sensitive data
         command.env_clear();
@@ -535,16 +1301,8 @@ impl OgImageGenerator {
             command.env("PATH", path);
         }
 
-        let output = command.output().await;
-        let duration = start_time.elapsed();
-
-        match output {
-            Ok(output) if output.status.success() => {
-                debug!(
-                    duration_ms = duration.as_millis(),
-                    "PNG optimization completed successfully"
-                );
-            }
+        match command.output().await {
+            Ok(output) if output.status.success() => {}
             Ok(output) => {
                 let stderr = String::from_utf8_lossy(&output.stderr);
                 let stdout = String::from_utf8_lossy(&output.stdout);
@@ -552,18 +1310,47 @@ impl OgImageGenerator {
                     exit_code = ?output.status.code(),
                     stderr = %stderr,
                     stdout = %stdout,
-                    duration_ms = duration.as_millis(),
-                    input_file = %png_file.display(),
+                    input_file = %png_file.path().display(),
                     "PNG optimization failed, continuing with unoptimized image"
                 );
+                return None;
             }
             Err(err) => {
                 warn!(
                     error = %err,
-                    input_file = %png_file.display(),
+                    input_file = %png_file.path().display(),
                     oxipng_path = %self.oxipng_binary_path.display(),
                     "Failed to execute oxipng, continuing with unoptimized image"
                 );
+                return None;
+            }
+        }
+
+        fs::read(png_file.path())
+            .await
+            .inspect_err(|err| warn!(error = %err, "Failed to read optimized PNG"))
+            .ok()
+    }
+
+    /// Optimizes a PNG image in-memory using the `oxipng` crate.
+    #[cfg(feature = "embedded-oxipng")]
+    async fn optimize_png_embedded(&self, png: &[u8]) -> Option<Vec<u8>> {
+        let mut options = oxipng::Options::from_preset(self.png_optimization_level);
+        options.strip = self.png_strip_mode.to_oxipng();
+
+        let png = png.to_vec();
+        let result =
+            tokio::task::spawn_blocking(move || oxipng::optimize_from_memory(&png, &options));
+
+        match result.await {
+            Ok(Ok(optimized)) => Some(optimized),
+            Ok(Err(err)) => {
+                warn!(error = %err, "PNG optimization failed, continuing with unoptimized image");
+                None
+            }
+            Err(err) => {
+                warn!(error = %err, "PNG optimization task failed, continuing with unoptimized image");
+                None
             }
         }
     }
@@ -575,9 +1362,26 @@ impl Default for OgImageGenerator {
     /// Uses "typst" and "oxipng" as default binary paths, assuming they are available in PATH.
     fn default() -> Self {
         Self {
+            backend: RenderBackend::default(),
+            renderer: None,
+            template: Template::bundled(),
+            branding: Branding::crates_io(),
+            fonts: Self::DEFAULT_FONTS
+                .iter()
+                .map(|&font| font.into())
+                .collect(),
+            avatar_concurrency: Self::DEFAULT_AVATAR_CONCURRENCY,
+            avatar_fetch_options: AvatarFetchOptions::default(),
+            http_client: OnceLock::new(),
             typst_binary_path: PathBuf::from("typst"),
             typst_font_path: None,
+            ppi: DEFAULT_PPI,
             oxipng_binary_path: PathBuf::from("oxipng"),
+            png_optimizer: PngOptimizerBackend::default(),
+            png_optimization_level: 2,
+            png_strip_mode: PngStripMode::Safe,
+            #[cfg(feature = "embedded-typst")]
+            font_cache: Default::default(),
         }
     }
 }
@@ -586,6 +1390,7 @@ impl Default for OgImageGenerator {
 mod tests {
     use super::*;
     use mockito::{Server, ServerGuard};
+    use std::sync::Mutex;
     use tracing::dispatcher::DefaultGuard;
     use tracing::{Level, subscriber};
     use tracing_subscriber::fmt;
@@ -659,6 +1464,9 @@ mod tests {
             lines_of_code: None,
             crate_size: 10000,
             releases: 1,
+            downloads: None,
+            recent_downloads: None,
+            download_history: &[],
         }
     }
 
@@ -690,6 +1498,9 @@ mod tests {
             lines_of_code: Some(42),
             crate_size: 256256,
             releases: 5,
+            downloads: None,
+            recent_downloads: None,
+            download_history: &[],
         }
     }
 
@@ -733,6 +1544,9 @@ mod tests {
             lines_of_code: Some(147000),
             crate_size: 2847123,
             releases: 1432,
+            downloads: None,
+            recent_downloads: None,
+            download_history: &[],
         }
     }
 
@@ -749,10 +1563,18 @@ mod tests {
             lines_of_code: Some(1000),
             crate_size: 42012,
             releases: 1,
+            downloads: None,
+            recent_downloads: None,
+            download_history: &[],
         }
     }
 
     fn skip_if_typst_unavailable() -> bool {
+        if cfg!(feature = "embedded-typst") {
+            // The template is compiled in-process, no Typst binary required.
+            return false;
+        }
+
         if matches!(var("CI"), Ok(Some(_))) {
             // Do not skip tests in CI environments, even if Typst is unavailable.
             // We want the test to fail instead of silently skipping.
@@ -768,20 +1590,51 @@ mod tests {
             .is_err()
     }
 
-    async fn generate_image(data: OgImageData<'_>) -> Option<Vec<u8>> {
-        if skip_if_typst_unavailable() {
-            return None;
+    /// Returns `true` if the fonts of the generator are neither installed
+    /// nor available in the `TYPST_FONT_PATH`, in which case every
+    /// character would be reported as unsupported.
+    #[cfg(feature = "embedded-typst")]
+    async fn skip_if_fonts_unavailable(generator: &OgImageGenerator) -> bool {
+        if matches!(var("CI"), Ok(Some(_))) {
+            // Do not skip tests in CI environments, even if the fonts are unavailable.
+            return false;
+        }
+
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default();
+        let missing = generator.unsupported_chars(&data, &options).await.unwrap();
+        if !missing.is_empty() {
+            eprintln!("Skipping test: fonts not found, set TYPST_FONT_PATH to their directory");
         }
+        !missing.is_empty()
+    }
 
+    /// A font that is embedded in Typst, for snapshots that must not depend
+    /// on the fonts that are installed or available in the `TYPST_FONT_PATH`.
+    const EMBEDDED_FONT: &str = "Libertinus Serif";
+
+    async fn generate_image(data: OgImageData<'_>) -> Option<Vec<u8>> {
         let generator =
             OgImageGenerator::from_environment().expect("Failed to create OgImageGenerator");
 
-        let temp_file = generator
-            .generate(data)
+        generate_image_with(generator, data).await
+    }
+
+    async fn generate_image_with(
+        generator: OgImageGenerator,
+        data: OgImageData<'_>,
+    ) -> Option<Vec<u8>> {
+        if skip_if_typst_unavailable() {
+            return None;
+        }
+
+        let options = GenerateOptions::default();
+        let image = generator
+            .generate_to_bytes(data, &options)
             .await
             .expect("Failed to generate image");
 
-        Some(std::fs::read(temp_file.path()).expect("Failed to read generated image"))
+        Some(image.bytes.to_vec())
     }
 
     #[tokio::test]
@@ -834,6 +1687,593 @@ mod tests {
         }
     }
 
+    /// A renderer that records all requests and returns a fixed PNG image,
+    /// so that the generation pipeline can be tested without Typst.
+    #[derive(Default)]
+    struct FakeRenderer {
+        requests: Arc<Mutex<Vec<RenderRequest>>>,
+    }
+
+    impl Renderer for FakeRenderer {
+        fn render(&self, request: RenderRequest) -> RenderFuture<'_> {
+            let image: &[u8] = match request.format {
+                RenderFormat::Png => include_bytes!("../template/assets/test-avatar.png"),
+                RenderFormat::Svg => br#"<svg xmlns="http://www.w3.org/2000/svg"/>"#,
+                RenderFormat::Pdf => b"%PDF-1.7",
+            };
+
+            self.requests.lock().unwrap().push(request);
+            Box::pin(async { Ok(image.to_vec()) })
+        }
+    }
+
+    /// Generates an image with a [`FakeRenderer`] instead of the renderer of
+    /// the `generator`, and returns the recorded render request.
+    async fn render_request(
+        generator: OgImageGenerator,
+        data: OgImageData<'_>,
+        options: &GenerateOptions,
+    ) -> RenderRequest {
+        let renderer = FakeRenderer::default();
+        let requests = renderer.requests.clone();
+        let generator = generator.with_renderer(renderer);
+        generator
+            .generate_with_options(data, options)
+            .await
+            .unwrap();
+
+        let mut requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        requests.pop().unwrap()
+    }
+
+    /// Parses the JSON value of the given template input.
+    fn input_json(request: &RenderRequest, key: &str) -> serde_json::Value {
+        serde_json::from_str(&request.inputs[key]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let server = create_mock_avatar_server().await;
+        let server_url = server.url();
+
+        let authors = create_escaping_authors(&server_url);
+        let data = create_escaping_test_data(&authors);
+
+        let renderer = FakeRenderer::default();
+        let requests = renderer.requests.clone();
+        let generator = OgImageGenerator::default().with_renderer(renderer);
+
+        let image = generator.generate(data).await.unwrap();
+        let image_data = std::fs::read(image.path()).unwrap();
+        assert_eq!(
+            OgImageGenerator::detect_image_format(&image_data),
+            Some("png")
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+
+        let request = &requests[0];
+        assert_eq!(request.format, RenderFormat::Png);
+        assert_eq!(request.ppi, DEFAULT_PPI);
+        assert_eq!(request.template, Template::bundled().source());
+        assert!(request.assets.contains_key("assets/cargo.png"));
+        assert!(request.assets.contains_key("assets/avatar_0.png"));
+
+        let data = input_json(request, "data");
+        assert_eq!(data["name"], "crate-with-\"quotes\"");
+        assert_eq!(data["crate_size"], "250 KiB");
+        assert_eq!(data["downloads"], serde_json::Value::Null);
+
+        let avatar_map = input_json(request, "avatar_map");
+        let avatar_url = format!("{server_url}/test-avatar.png");
+        assert_eq!(
+            avatar_map,
+            serde_json::json!({ avatar_url: "avatar_0.png" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_densities_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let renderer = FakeRenderer::default();
+        let requests = renderer.requests.clone();
+        let generator = OgImageGenerator::default()
+            .with_renderer(renderer)
+            .with_ppi(72.)
+            .unwrap();
+
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default();
+        let images = generator.generate_densities(data, &options, &[144., 288.]);
+        let images = images.await.unwrap();
+        assert_eq!(images.len(), 2);
+        assert_eq!((images[0].width, images[0].height), (1200, 630));
+        assert_eq!((images[1].width, images[1].height), (2400, 1260));
+
+        let requests = requests.lock().unwrap();
+        let ppis = requests
+            .iter()
+            .map(|request| request.ppi)
+            .collect::<Vec<_>>();
+        assert_eq!(ppis, [144., 288.]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_ppi() {
+        let _guard = init_tracing();
+
+        for ppi in [0., -144., f32::NAN, f32::INFINITY] {
+            let result = OgImageGenerator::default().with_ppi(ppi);
+            assert!(matches!(result, Err(OgImageError::InvalidPpi(_))));
+        }
+
+        let renderer = FakeRenderer::default();
+        let requests = renderer.requests.clone();
+        let generator = OgImageGenerator::default().with_renderer(renderer);
+
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default();
+        let result = generator.generate_densities(data, &options, &[144., 0.]);
+        assert!(matches!(result.await, Err(OgImageError::InvalidPpi(0.))));
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generate_preset_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let generator = OgImageGenerator::default();
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default().with_preset(CardPreset::Twitter);
+        let request = render_request(generator, data, &options).await;
+
+        let preset = input_json(&request, "preset");
+        let expected = serde_json::json!({ "width": 600., "height": 300., "layout": "wide" });
+        assert_eq!(preset, expected);
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_custom_template() {
+        let _guard = init_tracing();
+
+        let source = "#let data = json(bytes(sys.inputs.data))\n= #data.name";
+        let template = Template::new(source).unwrap();
+        let template = template.with_asset("logo.svg", b"<svg/>".as_slice());
+
+        let generator = OgImageGenerator::default().with_template(template.unwrap());
+        let data = create_simple_test_data();
+        let request = render_request(generator, data, &GenerateOptions::default()).await;
+
+        assert_eq!(request.template, source);
+        assert!(request.assets.contains_key("logo.svg"));
+        assert!(!request.assets.contains_key("assets/cargo.png"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_theme_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let generator = OgImageGenerator::default();
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default().with_theme(Theme::dark());
+        let request = render_request(generator, data, &options).await;
+
+        let theme = input_json(&request, "theme");
+        assert_eq!(theme["background"]["lightness"], 0.22);
+    }
+
+    #[tokio::test]
+    async fn test_generate_theme_with_insufficient_contrast() {
+        let _guard = init_tracing();
+
+        let renderer = FakeRenderer::default();
+        let requests = renderer.requests.clone();
+        let generator = OgImageGenerator::default().with_renderer(renderer);
+
+        let theme = Theme {
+            text: Theme::light().background,
+            ..Theme::light()
+        };
+
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default().with_theme(theme);
+        let result = generator.generate_with_options(data, &options).await;
+        assert!(matches!(
+            result,
+            Err(OgImageError::InsufficientContrast { .. })
+        ));
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generate_branding_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let watermark = br#"<svg xmlns="http://www.w3.org/2000/svg"/>"#;
+        let branding = Branding::new("registry.example.com").with_watermark(watermark.as_slice());
+        let generator = OgImageGenerator::default().with_branding(branding.unwrap());
+        let data = create_simple_test_data();
+        let request = render_request(generator, data, &GenerateOptions::default()).await;
+        assert!(request.assets.contains_key("assets/branding/watermark.svg"));
+
+        let branding = input_json(&request, "branding");
+        assert_eq!(branding["name"], "registry.example.com");
+        assert_eq!(branding["logo"], "assets/cargo.png");
+    }
+
+    #[tokio::test]
+    async fn test_generate_downloads_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let generator = OgImageGenerator::default();
+        let data = OgImageData {
+            downloads: Some(12_345_678),
+            recent_downloads: Some(4_567),
+            download_history: &[3, 1, 4],
+            ..create_simple_test_data()
+        };
+        let request = render_request(generator, data, &GenerateOptions::default()).await;
+        assert!(request.assets.contains_key("assets/download.svg"));
+
+        let data = input_json(&request, "data");
+        assert_eq!(data["download_history"], serde_json::json!([3, 1, 4]));
+        assert_eq!(data["downloads"], "12M");
+        assert_eq!(data["recent_downloads"], "4.6K");
+    }
+
+    #[tokio::test]
+    async fn test_generate_locale_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let generator = OgImageGenerator::default();
+        let data = OgImageData {
+            downloads: Some(12_345_678),
+            ..create_simple_test_data()
+        };
+        let options = GenerateOptions::default().with_locale(Locale::German);
+        let request = render_request(generator, data, &options).await;
+
+        let data = input_json(&request, "data");
+        assert_eq!(data["crate_size"], "41,0 KiB");
+        assert_eq!(data["lines_of_code"], "1.000");
+        assert_eq!(data["downloads"], "12 Mio.");
+
+        let labels = input_json(&request, "labels");
+        assert_eq!(labels["releases"], "Versionen");
+        assert_eq!(labels["authors_prefix"], "von ");
+    }
+
+    #[tokio::test]
+    async fn test_generate_rtl_description_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let authors = [author("דוד"), author("alice")];
+        let data = OgImageData {
+            description: Some("مكتبة سريعة لتحليل JSON"),
+            authors: &authors,
+            ..create_simple_test_data()
+        };
+        let options = GenerateOptions::default();
+        let request = render_request(OgImageGenerator::default(), data, &options).await;
+
+        let data = input_json(&request, "data");
+        let expected = serde_json::json!({ "dir": "rtl", "lang": "ar" });
+        assert_eq!(data["description_script"], expected);
+        assert_eq!(data["authors"][0]["name"], "דוד");
+        let expected = serde_json::json!({ "dir": "rtl", "lang": "he" });
+        assert_eq!(data["authors"][0]["script"], expected);
+        let expected = serde_json::json!({ "dir": "ltr", "lang": null });
+        assert_eq!(data["authors"][1]["script"], expected);
+
+        let data = OgImageData {
+            description: None,
+            ..create_simple_test_data()
+        };
+        let request = render_request(OgImageGenerator::default(), data, &options).await;
+
+        let data = input_json(&request, "data");
+        assert_eq!(data["description_script"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_generate_to_bytes_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let generator = OgImageGenerator::default().with_renderer(FakeRenderer::default());
+
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default().with_preset(CardPreset::Square);
+        let image = generator.generate_to_bytes(data, &options).await.unwrap();
+        assert_eq!(image.mime_type(), "image/png");
+        assert_eq!((image.width, image.height), (800, 800));
+        assert_eq!(
+            OgImageGenerator::detect_image_format(&image.bytes),
+            Some("png")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_into_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let generator = OgImageGenerator::default().with_renderer(FakeRenderer::default());
+
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default().with_format(OutputFormat::Svg);
+        let mut output = Vec::new();
+        let result = generator.generate_into(data, &options, &mut output).await;
+        result.unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("<svg"), "{output}");
+    }
+
+    #[tokio::test]
+    async fn test_generate_svg_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let renderer = FakeRenderer::default();
+        let requests = renderer.requests.clone();
+        let generator = OgImageGenerator::default().with_renderer(renderer);
+
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default().with_format(OutputFormat::Svg);
+        let image = generator.generate_with_options(data, &options).await;
+        let image = image.unwrap();
+        assert_eq!(image.format, OutputFormat::Svg);
+        assert_eq!((image.width, image.height), (600, 315));
+        assert_eq!(image.optimization, None);
+        assert_eq!(image.path().extension().unwrap(), "svg");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].format, RenderFormat::Svg);
+    }
+
+    #[tokio::test]
+    async fn test_generate_pdf_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let renderer = FakeRenderer::default();
+        let requests = renderer.requests.clone();
+        let generator = OgImageGenerator::default().with_renderer(renderer);
+
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default().with_format(OutputFormat::Pdf);
+        let image = generator.generate_with_options(data, &options).await;
+        let image = image.unwrap();
+        assert_eq!(image.format, OutputFormat::Pdf);
+        assert_eq!(image.optimization, None);
+        assert_eq!(image.path().extension().unwrap(), "pdf");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].format, RenderFormat::Pdf);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_avatar_downloads() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let _guard = init_tracing();
+
+        // Every response is delayed and counted while it is in flight, to
+        // check that the downloads overlap without exceeding the limit
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+        let mut server = Server::new_async().await;
+        let responses: [(&str, usize, &'static [u8]); 4] = [
+            (
+                "/test-avatar.png",
+                200,
+                include_bytes!("../template/assets/test-avatar.png"),
+            ),
+            ("/missing-avatar.png", 404, b"Not Found"),
+            (
+                "/test-avatar.webp",
+                200,
+                include_bytes!("../template/assets/test-avatar.webp"),
+            ),
+            (
+                "/test-avatar.jpg",
+                200,
+                include_bytes!("../template/assets/test-avatar.jpg"),
+            ),
+        ];
+        for (path, status, body) in responses {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            server
+                .mock("GET", path)
+                .with_status(status)
+                .with_chunked_body(move |writer| {
+                    let count = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(count, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(200));
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    writer.write_all(body)
+                })
+                .create_async()
+                .await;
+        }
+        let server_url = server.url();
+
+        let authors = vec![
+            author_with_avatar("png", format!("{server_url}/test-avatar.png")),
+            author_with_avatar("missing", format!("{server_url}/missing-avatar.png")),
+            author("no-avatar"),
+            author_with_avatar("webp", format!("{server_url}/test-avatar.webp")),
+            author_with_avatar("jpg", format!("{server_url}/test-avatar.jpg")),
+        ];
+        let data = OgImageData {
+            authors: &authors,
+            ..create_simple_test_data()
+        };
+
+        let generator = OgImageGenerator::default().with_avatar_concurrency(2);
+        let request = render_request(generator, data, &GenerateOptions::default()).await;
+
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+        assert!(request.assets.contains_key("assets/avatar_0.png"));
+        assert!(request.assets.contains_key("assets/avatar_4.jpg"));
+
+        let avatar_map = input_json(&request, "avatar_map");
+        let png_url = format!("{server_url}/test-avatar.png");
+        let jpg_url = format!("{server_url}/test-avatar.jpg");
+        let expected = serde_json::json!({ png_url: "avatar_0.png", jpg_url: "avatar_4.jpg" });
+        assert_eq!(avatar_map, expected);
+    }
+
+    #[tokio::test]
+    async fn test_generate_fonts_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let options = GenerateOptions::default();
+        let generator = OgImageGenerator::default();
+        let request = render_request(generator, create_simple_test_data(), &options).await;
+        let expected = serde_json::json!(OgImageGenerator::DEFAULT_FONTS);
+        assert_eq!(input_json(&request, "fonts"), expected);
+
+        let generator = OgImageGenerator::default().with_fonts(["Fira Sans", "Noto Sans CJK SC"]);
+        let request = render_request(generator, create_simple_test_data(), &options).await;
+        let expected = serde_json::json!(["Fira Sans", "Noto Sans CJK SC"]);
+        assert_eq!(input_json(&request, "fonts"), expected);
+    }
+
+    #[cfg(feature = "embedded-typst")]
+    #[tokio::test]
+    async fn test_unsupported_chars() {
+        let _guard = init_tracing();
+
+        let generator = OgImageGenerator::from_environment()
+            .unwrap()
+            .with_fonts(["Fira Sans"]);
+
+        if skip_if_fonts_unavailable(&generator).await {
+            return;
+        }
+
+        let data = OgImageData {
+            description: Some("A fast JSON parser \u{2014} 高速 🚀\u{FE0F}"),
+            ..create_simple_test_data()
+        };
+        let options = GenerateOptions::default();
+        let missing = generator.unsupported_chars(&data, &options).await.unwrap();
+        assert_eq!(missing.into_iter().collect::<String>(), "速高🚀");
+    }
+
+    #[cfg(feature = "embedded-typst")]
+    #[tokio::test]
+    async fn test_unsupported_chars_in_labels_and_branding() {
+        let _guard = init_tracing();
+
+        let generator = OgImageGenerator::from_environment()
+            .unwrap()
+            .with_fonts(["Fira Sans"]);
+
+        if skip_if_fonts_unavailable(&generator).await {
+            return;
+        }
+
+        let data = create_simple_test_data();
+        let options = GenerateOptions::default().with_locale(Locale::Japanese);
+        let missing = generator.unsupported_chars(&data, &options).await.unwrap();
+        // "最新" is the label of the latest version
+        assert!(missing.contains(&'最'), "{missing:?}");
+        assert!(missing.contains(&'新'), "{missing:?}");
+
+        let generator = generator.with_branding(Branding::new("登録所"));
+        let options = GenerateOptions::default();
+        let missing = generator.unsupported_chars(&data, &options).await.unwrap();
+        assert_eq!(missing.into_iter().collect::<String>(), "所登録");
+    }
+
+    #[cfg(feature = "transcode")]
+    #[tokio::test]
+    async fn test_generate_jpeg_with_custom_renderer() {
+        let _guard = init_tracing();
+
+        let renderer = FakeRenderer::default();
+        let requests = renderer.requests.clone();
+        let generator = OgImageGenerator::default().with_renderer(renderer);
+
+        let data = create_simple_test_data();
+        let format = OutputFormat::Jpeg { quality: 80 };
+        let options = GenerateOptions::default().with_format(format);
+        let image = generator.generate_with_options(data, &options).await;
+        let image = image.unwrap();
+        assert_eq!(image.mime_type(), "image/jpeg");
+        assert_eq!(image.optimization, None);
+
+        let image_data = std::fs::read(image.path()).unwrap();
+        assert_eq!(
+            OgImageGenerator::detect_image_format(&image_data),
+            Some("jpg")
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].format, RenderFormat::Png);
+    }
+
+    #[cfg(not(feature = "transcode"))]
+    #[tokio::test]
+    async fn test_generate_webp_without_transcode_feature() {
+        let _guard = init_tracing();
+
+        let generator = OgImageGenerator::default().with_renderer(FakeRenderer::default());
+
+        let data = create_simple_test_data();
+        let format = OutputFormat::WebP { quality: None };
+        let options = GenerateOptions::default().with_format(format);
+        let error = generator.generate_with_options(data, &options).await;
+        let error = error.unwrap_err();
+        assert!(matches!(
+            error,
+            OgImageError::FormatUnavailable {
+                feature: "transcode",
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_generate_og_image_all_fields_snapshot() {
+        let _guard = init_tracing();
+
+        static AUTHORS: &[OgImageAuthorData<'_>] = &[author("alice"), author("bob")];
+        let download_history = (0..90)
+            .map(|day| 300_000 + day * 2_000 + (day % 7) * 40_000)
+            .collect::<Vec<_>>();
+
+        let data = OgImageData {
+            name: "all-fields",
+            version: "1.0.219",
+            description: Some("A crate with every optional field of the card populated"),
+            license: Some("MIT OR Apache-2.0"),
+            tags: &["testing", "metadata", "downloads"],
+            authors: AUTHORS,
+            lines_of_code: Some(12_345),
+            crate_size: 78_901,
+            releases: 321,
+            downloads: Some(543_210_987),
+            recent_downloads: Some(34_567_890),
+            download_history: &download_history,
+        };
+
+        let generator = OgImageGenerator::from_environment()
+            .unwrap()
+            .with_fonts([EMBEDDED_FONT]);
+
+        if let Some(image_data) = generate_image_with(generator, data).await {
+            insta::assert_binary_snapshot!("all-fields.png", image_data);
+        }
+    }
+
     #[tokio::test]
     async fn test_generate_og_image_with_404_avatar() {
         let _guard = init_tracing();
@@ -856,6 +2296,9 @@ mod tests {
             lines_of_code: Some(1000),
             crate_size: 42012,
             releases: 1,
+            downloads: None,
+            recent_downloads: None,
+            download_history: &[],
         };
 
         if let Some(image_data) = generate_image(data).await {
@@ -884,10 +2327,49 @@ mod tests {
             lines_of_code: Some(5000),
             crate_size: 128000,
             releases: 3,
+            downloads: None,
+            recent_downloads: None,
+            download_history: &[],
         };
 
         if let Some(image_data) = generate_image(data).await {
             insta::assert_binary_snapshot!("unicode-truncation.png", image_data);
         }
     }
+
+    #[tokio::test]
+    async fn test_generate_og_image_rtl_truncation() {
+        let _guard = init_tracing();
+
+        // The ellipsis of truncated right-to-left text has to end up at its
+        // visual end, i.e. on the left
+        static AUTHORS: &[OgImageAuthorData<'_>] = &[author("דוד לוי"), author("alice")];
+
+        let data = OgImageData {
+            name: "hebrew-parser",
+            version: "1.0.0",
+            description: Some(
+                "ספרייה מהירה לניתוח קבצי JSON עם תמיכה מלאה בזרמים, בשגיאות מפורטות ובקידוד UTF-8. התיאור הזה ארוך מאוד כדי לוודא שהטקסט נחתך כאשר הוא לא נכנס למקום הפנוי בכרטיס. אנחנו מוסיפים עוד ועוד טקסט כדי שסימן ההשמטה יופיע בסוף הוויזואלי של השורה האחרונה, כלומר בצד שמאל. ועוד משפט אחד ארוך כדי להיות בטוחים שהטקסט אכן חורג מהגובה המותר של התיאור בכרטיס.",
+            ),
+            license: Some("MIT"),
+            tags: &["json", "parser", "rtl"],
+            authors: AUTHORS,
+            lines_of_code: Some(5000),
+            crate_size: 128000,
+            releases: 3,
+            downloads: None,
+            recent_downloads: None,
+            download_history: &[],
+        };
+
+        // Fira Sans does not cover Hebrew, so the snapshot would depend on
+        // the fallback fonts of the host
+        let generator = OgImageGenerator::from_environment()
+            .unwrap()
+            .with_fonts([EMBEDDED_FONT]);
+
+        if let Some(image_data) = generate_image_with(generator, data).await {
+            insta::assert_binary_snapshot!("rtl-truncation.png", image_data);
+        }
+    }
 }
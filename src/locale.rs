@@ -0,0 +1,243 @@
+//! Locales of the generated cards, including the number formatting
+//! conventions and the translated card labels.
+
+use crate::OgImageError;
+use serde::Serialize;
+
+/// The language and number formatting conventions of the generated card.
+///
+/// The locale determines the number formatting and the labels of the card,
+/// e.g. the metadata titles and the "by alice and bob" author line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    /// English, e.g. "1.5K" and "1499".
+    #[default]
+    English,
+    /// German, e.g. "1,5 Tsd." and "1.499".
+    German,
+    /// Spanish, e.g. "1,5 mil" and "1.499".
+    Spanish,
+    /// Japanese, e.g. "1.5万" and "9,999".
+    ///
+    /// The bundled fonts do not contain Japanese glyphs, so the labels are
+    /// only rendered correctly with a font that covers them.
+    Japanese,
+}
+
+impl Locale {
+    /// Returns the character between the integer and the fractional part.
+    fn decimal_separator(&self) -> char {
+        match self {
+            Self::English | Self::Japanese => '.',
+            Self::German | Self::Spanish => ',',
+        }
+    }
+
+    /// Returns the character between groups of thousands, if any.
+    ///
+    /// English numbers are not grouped to keep them at up to 4 characters.
+    fn grouping_separator(&self) -> Option<char> {
+        match self {
+            Self::English => None,
+            Self::German | Self::Spanish => Some('.'),
+            Self::Japanese => Some(','),
+        }
+    }
+
+    /// Returns the base between the number units, the threshold at which
+    /// the next unit is used, and the suffixes of the units.
+    ///
+    /// Japanese numbers are grouped in myriads (10,000) instead of
+    /// thousands, so they switch units at 10,000 of the current unit.
+    pub(crate) fn number_units(&self) -> (f64, f64, &'static [&'static str]) {
+        match self {
+            Self::English => (1000., 1500., &["", "K", "M", "B", "T"]),
+            Self::German => (1000., 1500., &["", " Tsd.", " Mio.", " Mrd.", " Bio."]),
+            Self::Spanish => (1000., 1500., &["", " mil", " M", " mil M", " B"]),
+            Self::Japanese => (10000., 10000., &["", "万", "億", "兆"]),
+        }
+    }
+
+    /// Applies the decimal and grouping separators to a number that was
+    /// formatted with a `.` decimal separator and without grouping.
+    pub(crate) fn localize(&self, number: &str) -> String {
+        let (integer, fraction) = match number.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (number, None),
+        };
+
+        let mut result = String::with_capacity(number.len() + integer.len() / 3);
+        for (index, digit) in integer.chars().enumerate() {
+            let remaining = integer.len() - index;
+            if index > 0 && remaining % 3 == 0 {
+                result.extend(self.grouping_separator());
+            }
+            result.push(digit);
+        }
+
+        if let Some(fraction) = fraction {
+            result.push(self.decimal_separator());
+            result.push_str(fraction);
+        }
+
+        result
+    }
+
+    /// Returns the translated labels of the card.
+    fn labels(&self) -> Labels {
+        match self {
+            Self::English => Labels {
+                releases: "Releases",
+                latest: "Latest",
+                downloads: "Downloads",
+                recent: "Recent",
+                license: "License",
+                sloc: "SLoC",
+                size: "Size",
+                authors_prefix: "by ",
+                authors_separator: ", ",
+                authors_and: " and ",
+                authors_others: Plural {
+                    one: Some(" and {n} other"),
+                    other: " and {n} others",
+                },
+            },
+            Self::German => Labels {
+                releases: "Versionen",
+                latest: "Aktuell",
+                downloads: "Downloads",
+                recent: "Kürzlich",
+                license: "Lizenz",
+                sloc: "SLoC",
+                size: "Größe",
+                authors_prefix: "von ",
+                authors_separator: ", ",
+                authors_and: " und ",
+                authors_others: Plural {
+                    one: Some(" und einer weiteren Person"),
+                    other: " und {n} weiteren Personen",
+                },
+            },
+            Self::Spanish => Labels {
+                releases: "Versiones",
+                latest: "Última",
+                downloads: "Descargas",
+                recent: "Recientes",
+                license: "Licencia",
+                sloc: "SLoC",
+                size: "Tamaño",
+                authors_prefix: "por ",
+                authors_separator: ", ",
+                authors_and: " y ",
+                authors_others: Plural {
+                    one: Some(" y {n} más"),
+                    other: " y {n} más",
+                },
+            },
+            // Japanese has no grammatical plural, so only the `other`
+            // form is used.
+            Self::Japanese => Labels {
+                releases: "リリース",
+                latest: "最新",
+                downloads: "ダウンロード",
+                recent: "最近",
+                license: "ライセンス",
+                sloc: "コード行数",
+                size: "サイズ",
+                authors_prefix: "作者: ",
+                authors_separator: "、",
+                authors_and: "、",
+                authors_others: Plural {
+                    one: None,
+                    other: " 他{n}名",
+                },
+            },
+        }
+    }
+
+    /// Returns the texts of the labels without the `{n}` placeholders, e.g.
+    /// to check that the fonts cover them.
+    #[cfg(feature = "embedded-typst")]
+    pub(crate) fn label_texts(self) -> Vec<String> {
+        let labels = self.labels();
+        let Plural { one, other } = labels.authors_others;
+
+        [
+            labels.releases,
+            labels.latest,
+            labels.downloads,
+            labels.recent,
+            labels.license,
+            labels.sloc,
+            labels.size,
+            labels.authors_prefix,
+            labels.authors_separator,
+            labels.authors_and,
+            other,
+        ]
+        .into_iter()
+        .chain(one)
+        .map(|text| text.replace("{n}", ""))
+        .collect()
+    }
+
+    /// Returns the `labels` input of the template.
+    pub(crate) fn to_input(self) -> Result<String, OgImageError> {
+        serde_json::to_string(&self.labels()).map_err(OgImageError::JsonSerializationError)
+    }
+}
+
+/// The translated labels of a card, as passed to the template.
+#[derive(Debug, Serialize)]
+struct Labels {
+    releases: &'static str,
+    latest: &'static str,
+    downloads: &'static str,
+    recent: &'static str,
+    license: &'static str,
+    sloc: &'static str,
+    size: &'static str,
+    /// Text before the list of authors, e.g. "by ".
+    authors_prefix: &'static str,
+    /// Text between all but the last two authors, e.g. ", ".
+    authors_separator: &'static str,
+    /// Text between the last two authors, e.g. " and ".
+    authors_and: &'static str,
+    /// Text after the list of authors if some of them are not shown,
+    /// e.g. " and {n} others", where `{n}` is the number of hidden authors.
+    authors_others: Plural,
+}
+
+/// The plural forms of a label.
+///
+/// The template uses the `one` form for a count of 1 if it is set, and the
+/// `other` form otherwise.
+#[derive(Debug, Serialize)]
+struct Plural {
+    one: Option<&'static str>,
+    other: &'static str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_to_input() {
+        let input = Locale::English.to_input().unwrap();
+        let input: serde_json::Value = serde_json::from_str(&input).unwrap();
+        assert_eq!(input["releases"], "Releases");
+        assert_eq!(input["authors_prefix"], "by ");
+        assert_eq!(input["authors_others"]["one"], " and {n} other");
+        assert_eq!(input["authors_others"]["other"], " and {n} others");
+    }
+
+    #[test]
+    fn test_japanese_to_input() {
+        let input = Locale::Japanese.to_input().unwrap();
+        let input: serde_json::Value = serde_json::from_str(&input).unwrap();
+        assert_eq!(input["size"], "サイズ");
+        assert_eq!(input["authors_others"]["one"], serde_json::Value::Null);
+        assert_eq!(input["authors_others"]["other"], " 他{n}名");
+    }
+}
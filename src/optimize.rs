@@ -0,0 +1,129 @@
+//! Types for configuring and reporting PNG optimization.
+
+/// The backend used to optimize the generated PNG images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PngOptimizerBackend {
+    /// Spawns the `oxipng` CLI for every image.
+    Cli,
+    /// Optimizes the image in-memory using the `oxipng` crate.
+    ///
+    /// Only available with the `embedded-oxipng` feature.
+    #[cfg(feature = "embedded-oxipng")]
+    Embedded,
+}
+
+impl Default for PngOptimizerBackend {
+    /// Uses the in-memory backend if the `embedded-oxipng` feature is
+    /// enabled, and falls back to the `oxipng` CLI otherwise.
+    fn default() -> Self {
+        #[cfg(feature = "embedded-oxipng")]
+        return Self::Embedded;
+        #[cfg(not(feature = "embedded-oxipng"))]
+        return Self::Cli;
+    }
+}
+
+/// Which metadata chunks are removed from the PNG during optimization.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PngStripMode {
+    /// Keep all metadata chunks.
+    None,
+    /// Remove all metadata chunks that do not affect how the image is displayed.
+    #[default]
+    Safe,
+    /// Remove all non-critical metadata chunks.
+    All,
+}
+
+impl PngStripMode {
+    /// Returns the value of the `--strip` argument of the `oxipng` CLI.
+    pub(crate) fn as_cli_arg(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Safe => Some("safe"),
+            Self::All => Some("all"),
+        }
+    }
+
+    #[cfg(feature = "embedded-oxipng")]
+    pub(crate) fn to_oxipng(self) -> oxipng::StripChunks {
+        match self {
+            Self::None => oxipng::StripChunks::None,
+            Self::Safe => oxipng::StripChunks::Safe,
+            Self::All => oxipng::StripChunks::All,
+        }
+    }
+}
+
+/// Size statistics of a successful PNG optimization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PngOptimization {
+    /// Size of the PNG in bytes before optimization
+    pub original_size: usize,
+    /// Size of the PNG in bytes after optimization
+    pub optimized_size: usize,
+}
+
+impl PngOptimization {
+    /// Returns the number of bytes saved by the optimization.
+    pub fn saved_bytes(&self) -> usize {
+        self.original_size.saturating_sub(self.optimized_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OgImageGenerator;
+    use std::path::PathBuf;
+
+    const PNG: &[u8] = include_bytes!("../template/assets/cargo.png");
+
+    /// Decodes a PNG into its pixel data, panicking if it is invalid.
+    #[cfg(feature = "embedded-oxipng")]
+    fn decode(png: &[u8]) -> Vec<u8> {
+        let decoder = png::Decoder::new(png);
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        pixels.truncate(info.buffer_size());
+        pixels
+    }
+
+    #[cfg(feature = "embedded-oxipng")]
+    #[tokio::test]
+    async fn test_optimize_embedded() {
+        let generator =
+            OgImageGenerator::default().with_png_optimizer(PngOptimizerBackend::Embedded);
+
+        let (optimized, optimization) = generator.optimize_png(PNG.to_vec()).await;
+        let optimization = optimization.unwrap();
+        assert_eq!(optimization.original_size, PNG.len());
+        assert_eq!(optimization.optimized_size, optimized.len());
+        assert!(optimized.len() <= PNG.len());
+
+        // The optimization is lossless, so the pixels must not change
+        assert_eq!(decode(&optimized), decode(PNG));
+    }
+
+    #[tokio::test]
+    async fn test_optimize_cli_missing_binary() {
+        let generator = OgImageGenerator::default()
+            .with_png_optimizer(PngOptimizerBackend::Cli)
+            .with_oxipng_path(PathBuf::from("/nonexistent/oxipng"));
+
+        // A missing binary is not an error, the image is kept unoptimized
+        let (png, optimization) = generator.optimize_png(PNG.to_vec()).await;
+        assert_eq!(png, PNG);
+        assert_eq!(optimization, None);
+    }
+
+    #[test]
+    fn test_saved_bytes() {
+        let optimization = PngOptimization {
+            original_size: 100,
+            optimized_size: 60,
+        };
+        assert_eq!(optimization.saved_bytes(), 40);
+    }
+}
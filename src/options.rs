@@ -0,0 +1,80 @@
+//! Per-call options for generating OpenGraph images.
+
+use crate::{CardPreset, Locale, OutputFormat, Theme};
+
+/// Options for a single [`OgImageGenerator::generate_with_options()`](crate::OgImageGenerator::generate_with_options) call.
+///
+/// # Examples
+///
+/// ```
+/// use crates_io_og_image::{CardPreset, GenerateOptions, OutputFormat, Theme};
+///
+/// let options = GenerateOptions::default()
+///     .with_format(OutputFormat::Svg)
+///     .with_preset(CardPreset::Square)
+///     .with_theme(Theme::dark());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptions {
+    format: OutputFormat,
+    preset: CardPreset,
+    theme: Theme,
+    locale: Locale,
+}
+
+impl GenerateOptions {
+    /// Sets the file format of the generated image.
+    ///
+    /// Defaults to [`OutputFormat::Png`].
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Returns the file format of the generated image.
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    /// Sets the size and layout of the generated card.
+    ///
+    /// Defaults to [`CardPreset::OpenGraph`].
+    pub fn with_preset(mut self, preset: CardPreset) -> Self {
+        self.preset = preset;
+        self
+    }
+
+    /// Returns the size and layout of the generated card.
+    pub fn preset(&self) -> CardPreset {
+        self.preset
+    }
+
+    /// Sets the colors of the generated card.
+    ///
+    /// The theme is [validated](Theme::validate) before the image is
+    /// generated. Defaults to [`Theme::light()`].
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Returns the colors of the generated card.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Sets the locale used to format the numbers and sizes and to translate
+    /// the labels on the card.
+    ///
+    /// Defaults to [`Locale::English`].
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Returns the locale used to format the numbers and sizes and to
+    /// translate the labels on the card.
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+}
@@ -0,0 +1,98 @@
+//! Output formats of the generated OpenGraph images.
+
+/// The file format of a generated OpenGraph image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A PNG raster image, optimized with oxipng.
+    #[default]
+    Png,
+    /// A resolution-independent SVG image.
+    ///
+    /// Avatars and icons are embedded inline, so the SVG is self-contained
+    /// and can be embedded in web pages without any additional files.
+    Svg,
+    /// A vector PDF document, e.g. for printing.
+    ///
+    /// All fonts are embedded, so the PDF renders identically on machines
+    /// without Fira Sans installed.
+    Pdf,
+    /// A WebP raster image, transcoded from the rendered PNG.
+    ///
+    /// Encodes lossless if `quality` is `None`, and lossy with the given
+    /// quality (0-100) otherwise. Requires the `transcode` feature.
+    WebP { quality: Option<u8> },
+    /// A JPEG raster image with the given quality (1-100), transcoded from
+    /// the rendered PNG.
+    ///
+    /// Requires the `transcode` feature.
+    Jpeg { quality: u8 },
+    /// An AVIF raster image with the given quality (1-100), transcoded from
+    /// the rendered PNG.
+    ///
+    /// Requires the `avif` feature.
+    Avif { quality: u8 },
+}
+
+impl OutputFormat {
+    /// Returns the file extension of the format, without leading dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+            Self::Pdf => "pdf",
+            Self::WebP { .. } => "webp",
+            Self::Jpeg { .. } => "jpg",
+            Self::Avif { .. } => "avif",
+        }
+    }
+
+    /// Returns the MIME type of the format, e.g. for a `Content-Type` header.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Svg => "image/svg+xml",
+            Self::Pdf => "application/pdf",
+            Self::WebP { .. } => "image/webp",
+            Self::Jpeg { .. } => "image/jpeg",
+            Self::Avif { .. } => "image/avif",
+        }
+    }
+
+    /// Returns the format the template has to be rendered to before it can
+    /// be converted to this format.
+    pub fn render_format(&self) -> RenderFormat {
+        match self {
+            Self::Svg => RenderFormat::Svg,
+            Self::Pdf => RenderFormat::Pdf,
+            Self::Png | Self::WebP { .. } | Self::Jpeg { .. } | Self::Avif { .. } => {
+                RenderFormat::Png
+            }
+        }
+    }
+}
+
+/// The file formats that a [`Renderer`](crate::Renderer) can produce
+/// directly from the Typst template.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RenderFormat {
+    /// A PNG raster image.
+    #[default]
+    Png,
+    /// An SVG image with all images embedded inline.
+    Svg,
+    /// A PDF document with all fonts embedded.
+    Pdf,
+}
+
+impl RenderFormat {
+    /// Returns the file extension of the format, without leading dot.
+    ///
+    /// This is also the value of the `--format` argument of the Typst CLI.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+            Self::Pdf => "pdf",
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Owned variants of the image data, e.g. for job queue payloads.
+
+use crate::{OgImageAuthorData, OgImageData};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Owned variant of [`OgImageData`] that implements [`Deserialize`].
+///
+/// Unlike [`OgImageData`], which serializes the numbers in the formatted
+/// form that is passed to the template, this type serializes the raw
+/// numbers, so that it round-trips through JSON.
+///
+/// # Examples
+///
+/// ```
+/// use crates_io_og_image::{OgImageDataOwned, OgImageGenerator};
+///
+/// # async fn generate(generator: OgImageGenerator) -> Result<(), Box<dyn std::error::Error>> {
+/// let payload = r#"{
+///     "name": "my-crate",
+///     "version": "1.0.0",
+///     "description": null,
+///     "license": "MIT",
+///     "tags": ["example"],
+///     "authors": [{ "name": "alice", "avatar": null }],
+///     "lines_of_code": 1234,
+///     "crate_size": 5678,
+///     "releases": 3,
+///     "downloads": 12345
+/// }"#;
+///
+/// let data: OgImageDataOwned = serde_json::from_str(payload)?;
+/// let image = generator.generate(data.to_borrowed().data()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OgImageDataOwned {
+    /// The crate name
+    pub name: String,
+    /// Latest version string (e.g., "1.0.210")
+    pub version: String,
+    /// Crate description text
+    pub description: Option<String>,
+    /// License information (e.g., "MIT/Apache-2.0")
+    pub license: Option<String>,
+    /// Keywords/categories for the crate
+    pub tags: Vec<String>,
+    /// Author information
+    pub authors: Vec<OgImageAuthorDataOwned>,
+    /// Source lines of code count (optional)
+    pub lines_of_code: Option<u32>,
+    /// Package size in bytes
+    pub crate_size: u64,
+    /// Total number of releases
+    pub releases: u32,
+    /// Total number of downloads (optional)
+    pub downloads: Option<u64>,
+    /// Number of downloads in the last 90 days (optional)
+    pub recent_downloads: Option<u64>,
+    /// Download counts in chronological order, e.g. 90 daily buckets
+    #[serde(default)]
+    pub download_history: Vec<u64>,
+}
+
+impl OgImageDataOwned {
+    /// Returns a borrowed view of the data, which can be turned into an
+    /// [`OgImageData`] using [`OgImageDataRef::data()`].
+    pub fn to_borrowed(&self) -> OgImageDataRef<'_> {
+        OgImageDataRef {
+            owned: self,
+            tags: self.tags.iter().map(String::as_str).collect(),
+            authors: self
+                .authors
+                .iter()
+                .map(|author| author.to_borrowed())
+                .collect(),
+        }
+    }
+}
+
+impl From<&OgImageData<'_>> for OgImageDataOwned {
+    fn from(data: &OgImageData<'_>) -> Self {
+        Self {
+            name: data.name.to_string(),
+            version: data.version.to_string(),
+            description: data.description.map(str::to_string),
+            license: data.license.map(str::to_string),
+            tags: data.tags.iter().map(|tag| tag.to_string()).collect(),
+            authors: data.authors.iter().map(Into::into).collect(),
+            lines_of_code: data.lines_of_code,
+            crate_size: data.crate_size,
+            releases: data.releases,
+            downloads: data.downloads,
+            recent_downloads: data.recent_downloads,
+            download_history: data.download_history.to_vec(),
+        }
+    }
+}
+
+impl From<OgImageData<'_>> for OgImageDataOwned {
+    fn from(data: OgImageData<'_>) -> Self {
+        Self::from(&data)
+    }
+}
+
+/// Owned variant of [`OgImageAuthorData`] that implements [`Deserialize`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OgImageAuthorDataOwned {
+    /// Author username/name
+    pub name: String,
+    /// Optional avatar URL
+    pub avatar: Option<String>,
+}
+
+impl OgImageAuthorDataOwned {
+    /// Returns the borrowed form of the author data.
+    pub fn to_borrowed(&self) -> OgImageAuthorData<'_> {
+        let avatar = self.avatar.as_deref().map(Cow::Borrowed);
+        OgImageAuthorData::new(&self.name, avatar)
+    }
+}
+
+impl From<&OgImageAuthorData<'_>> for OgImageAuthorDataOwned {
+    fn from(author: &OgImageAuthorData<'_>) -> Self {
+        Self {
+            name: author.name.to_string(),
+            avatar: author.avatar.as_ref().map(|avatar| avatar.to_string()),
+        }
+    }
+}
+
+/// A borrowed view of an [`OgImageDataOwned`].
+///
+/// [`OgImageData`] borrows its tags and authors as slices, so this type
+/// holds the slices that the [`data()`](Self::data) method borrows from.
+#[derive(Debug, Clone)]
+pub struct OgImageDataRef<'a> {
+    owned: &'a OgImageDataOwned,
+    tags: Vec<&'a str>,
+    authors: Vec<OgImageAuthorData<'a>>,
+}
+
+impl OgImageDataRef<'_> {
+    /// Returns the borrowed form of the data.
+    pub fn data(&self) -> OgImageData<'_> {
+        OgImageData {
+            name: &self.owned.name,
+            version: &self.owned.version,
+            description: self.owned.description.as_deref(),
+            license: self.owned.license.as_deref(),
+            tags: &self.tags,
+            authors: &self.authors,
+            lines_of_code: self.owned.lines_of_code,
+            crate_size: self.owned.crate_size,
+            releases: self.owned.releases,
+            downloads: self.owned.downloads,
+            recent_downloads: self.owned.recent_downloads,
+            download_history: &self.owned.download_history,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_owned_data() -> OgImageDataOwned {
+        OgImageDataOwned {
+            name: "test-crate".to_string(),
+            version: "1.0.0".to_string(),
+            description: Some("A test crate".to_string()),
+            license: None,
+            tags: vec!["testing".to_string(), "og-image".to_string()],
+            authors: vec![
+                OgImageAuthorDataOwned {
+                    name: "alice".to_string(),
+                    avatar: Some("https://example.com/alice.png".to_string()),
+                },
+                OgImageAuthorDataOwned {
+                    name: "bob".to_string(),
+                    avatar: None,
+                },
+            ],
+            lines_of_code: Some(1000),
+            crate_size: 42012,
+            releases: 7,
+            downloads: Some(1_234_567),
+            recent_downloads: None,
+            download_history: vec![10, 20, 15],
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let data = create_owned_data();
+
+        let json = serde_json::to_string(&data).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["crate_size"], 42012);
+        assert_eq!(value["authors"][1]["avatar"], serde_json::Value::Null);
+
+        let deserialized: OgImageDataOwned = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, data);
+    }
+
+    #[test]
+    fn test_borrowed_round_trip() {
+        let data = create_owned_data();
+
+        let borrowed = data.to_borrowed();
+        let borrowed = borrowed.data();
+        assert_eq!(borrowed.name, "test-crate");
+        assert_eq!(borrowed.tags, ["testing", "og-image"]);
+        assert_eq!(
+            borrowed.authors[0].avatar.as_deref(),
+            data.authors[0].avatar.as_deref()
+        );
+
+        assert_eq!(OgImageDataOwned::from(borrowed), data);
+    }
+}
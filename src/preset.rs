@@ -0,0 +1,82 @@
+//! Card presets for the aspect ratios of different platforms.
+
+/// The size and layout of the generated card.
+///
+/// The wide presets share the same layout and only differ in their page
+/// size, with the description taking up the remaining height. The square
+/// preset has a layout of its own, with a smaller crate name and the
+/// metadata arranged in a grid below the description. The pixel sizes
+/// below apply to the default density of 144 PPI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CardPreset {
+    /// The Open Graph layout with 1200×630 pixels (1.91:1).
+    #[default]
+    OpenGraph,
+    /// The Twitter `summary_large_image` layout with 1200×600 pixels (2:1).
+    Twitter,
+    /// The LinkedIn layout with 1200×627 pixels (1.91:1).
+    LinkedIn,
+    /// A square layout with 800×800 pixels (1:1), e.g. for Discord or
+    /// Mastodon thumbnails.
+    Square,
+}
+
+impl CardPreset {
+    /// Returns the page size of the preset as `(width, height)` in points.
+    pub fn page_size(&self) -> (f32, f32) {
+        match self {
+            Self::OpenGraph => (600., 315.),
+            Self::Twitter => (600., 300.),
+            Self::LinkedIn => (600., 313.5),
+            Self::Square => (400., 400.),
+        }
+    }
+
+    /// Returns the size of raster images as `(width, height)` in pixels at
+    /// the given pixel density.
+    pub fn pixel_size(&self, ppi: f32) -> (u32, u32) {
+        let (width, height) = self.page_size();
+        let scale = ppi / 72.;
+        (
+            (width * scale).round() as u32,
+            (height * scale).round() as u32,
+        )
+    }
+
+    /// Returns the name of the template layout used by the preset.
+    fn layout(&self) -> &'static str {
+        match self {
+            Self::OpenGraph | Self::Twitter | Self::LinkedIn => "wide",
+            Self::Square => "square",
+        }
+    }
+
+    /// Returns the `preset` input of the template.
+    pub(crate) fn to_input(self) -> String {
+        let (width, height) = self.page_size();
+        let layout = self.layout();
+        serde_json::json!({ "width": width, "height": height, "layout": layout }).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixel_size() {
+        assert_eq!(CardPreset::OpenGraph.pixel_size(144.), (1200, 630));
+        assert_eq!(CardPreset::OpenGraph.pixel_size(288.), (2400, 1260));
+        assert_eq!(CardPreset::Twitter.pixel_size(144.), (1200, 600));
+        assert_eq!(CardPreset::LinkedIn.pixel_size(144.), (1200, 627));
+        assert_eq!(CardPreset::Square.pixel_size(144.), (800, 800));
+    }
+
+    #[test]
+    fn test_to_input() {
+        let input = CardPreset::Square.to_input();
+        let input: serde_json::Value = serde_json::from_str(&input).unwrap();
+        let expected = serde_json::json!({ "width": 400., "height": 400., "layout": "square" });
+        assert_eq!(input, expected);
+    }
+}
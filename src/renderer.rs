@@ -0,0 +1,298 @@
+//! Renderers that turn the Typst template into an image.
+//!
+//! The [`Renderer`] trait decouples the image generation pipeline in
+//! [`OgImageGenerator`](crate::OgImageGenerator) from the way the template is
+//! actually compiled. The crate ships with [`TypstCliRenderer`], which spawns
+//! the `typst` CLI, and with `TypstEmbeddedRenderer`, which compiles the
+//! template in-process if the `embedded-typst` feature is enabled.
+
+use crate::{OgImageError, RenderFormat};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use tokio::fs;
+use tokio::process::Command;
+use tracing::{debug, error, info};
+
+#[cfg(feature = "embedded-typst")]
+pub use crate::embedded::TypstEmbeddedRenderer;
+
+/// The default pixel density of raster images, which matches the default of
+/// the Typst CLI.
+pub const DEFAULT_PPI: f32 = 144.;
+
+/// Checks that a pixel density is a finite positive number, which both
+/// renderers need to produce a non-empty image.
+pub(crate) fn validate_ppi(ppi: f32) -> Result<f32, OgImageError> {
+    if ppi.is_finite() && ppi > 0. {
+        Ok(ppi)
+    } else {
+        Err(OgImageError::InvalidPpi(ppi))
+    }
+}
+
+/// The future returned by the [`Renderer`] methods.
+pub type RenderFuture<'a, T = Vec<u8>> =
+    Pin<Box<dyn Future<Output = Result<T, OgImageError>> + Send + 'a>>;
+
+/// Everything a [`Renderer`] needs to render an OpenGraph image.
+#[derive(Debug, Clone)]
+pub struct RenderRequest {
+    /// Source code of the Typst template entry file
+    pub template: Cow<'static, str>,
+    /// Values passed to the template via `sys.inputs`
+    ///
+    /// This contains the serialized [`OgImageData`](crate::OgImageData) as
+    /// `data`, the mapping from avatar URLs to asset filenames as
+    /// `avatar_map`, the page size and layout of the
+    /// [`CardPreset`](crate::CardPreset) as `preset`, the colors of the
+    /// [`Theme`](crate::Theme) as `theme`, the registry name, logo and
+    /// watermark paths of the [`Branding`](crate::Branding) as `branding`,
+    /// the translated card labels of the [`Locale`](crate::Locale) as
+    /// `labels`, and the font fallback chain as `fonts`.
+    pub inputs: BTreeMap<&'static str, String>,
+    /// Files that can be read by the template, including downloaded avatars,
+    /// keyed by their path relative to the template entry file
+    pub assets: BTreeMap<String, Cow<'static, [u8]>>,
+    /// File format the template should be rendered to
+    pub format: RenderFormat,
+    /// Pixel density of raster images in pixels per inch
+    pub ppi: f32,
+}
+
+/// A backend that renders a [`RenderRequest`] into image bytes of the
+/// requested [`RenderFormat`].
+///
+/// # Examples
+///
+/// ```
+/// use crates_io_og_image::{OgImageGenerator, RenderFuture, RenderRequest, Renderer};
+///
+/// struct BlankRenderer;
+///
+/// impl Renderer for BlankRenderer {
+///     fn render(&self, _request: RenderRequest) -> RenderFuture<'_> {
+///         Box::pin(async { Ok(Vec::new()) })
+///     }
+/// }
+///
+/// let generator = OgImageGenerator::default().with_renderer(BlankRenderer);
+/// ```
+pub trait Renderer: Send + Sync {
+    /// Renders the template of the `request` and returns the image bytes.
+    fn render(&self, request: RenderRequest) -> RenderFuture<'_>;
+
+    /// Renders the template of the `request` once for every pixel density
+    /// in `ppis`, ignoring [`RenderRequest::ppi`].
+    ///
+    /// The default implementation calls [`render()`](Self::render) once per
+    /// density. Implementations can override this to share work between the
+    /// renders.
+    fn render_densities(
+        &self,
+        request: RenderRequest,
+        ppis: Vec<f32>,
+    ) -> RenderFuture<'_, Vec<Vec<u8>>> {
+        Box::pin(async move {
+            let mut images = Vec::with_capacity(ppis.len());
+            for ppi in ppis {
+                let request = RenderRequest {
+                    ppi,
+                    ..request.clone()
+                };
+                images.push(self.render(request).await?);
+            }
+            Ok(images)
+        })
+    }
+}
+
+/// The built-in renderer used by an [`OgImageGenerator`](crate::OgImageGenerator)
+/// if no custom [`Renderer`] is configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderBackend {
+    /// Spawns the Typst CLI for every image.
+    Cli,
+    /// Compiles the template in-process using the `typst` crates.
+    ///
+    /// Only available with the `embedded-typst` feature.
+    #[cfg(feature = "embedded-typst")]
+    Embedded,
+}
+
+impl Default for RenderBackend {
+    /// Uses the in-process backend if the `embedded-typst` feature is
+    /// enabled, and falls back to the Typst CLI otherwise.
+    fn default() -> Self {
+        #[cfg(feature = "embedded-typst")]
+        return Self::Embedded;
+        #[cfg(not(feature = "embedded-typst"))]
+        return Self::Cli;
+    }
+}
+
+/// A [`Renderer`] that spawns the Typst CLI for every image.
+#[derive(Debug, Clone)]
+pub struct TypstCliRenderer {
+    binary_path: PathBuf,
+    font_path: Option<PathBuf>,
+}
+
+impl TypstCliRenderer {
+    /// Creates a new `TypstCliRenderer` using the given Typst binary path
+    /// and optional font path.
+    ///
+    /// Setting a custom font directory implies using the
+    /// `--ignore-system-fonts` flag of the Typst CLI.
+    pub fn new(binary_path: PathBuf, font_path: Option<PathBuf>) -> Self {
+        Self {
+            binary_path,
+            font_path,
+        }
+    }
+
+    /// Renders the request once for every pixel density in `ppis`.
+    ///
+    /// The temporary directory with the template and assets is only created
+    /// once and shared between all Typst invocations.
+    async fn render_images(
+        &self,
+        request: RenderRequest,
+        ppis: &[f32],
+    ) -> Result<Vec<Vec<u8>>, OgImageError> {
+        // Create a temporary folder
+        let temp_dir = tempfile::tempdir().map_err(OgImageError::TempDirError)?;
+        debug!(temp_dir = %temp_dir.path().display(), "Created temporary directory");
+
+        // Copy the assets and avatars into the temporary folder
+        debug!("Copying assets to temporary directory");
+        for (path, bytes) in &request.assets {
+            let file_path = temp_dir.path().join(path);
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::write(&file_path, bytes).await?;
+        }
+
+        // Copy the Typst template file
+        let typ_file_path = temp_dir.path().join("og-image.typ");
+        debug!(template_path = %typ_file_path.display(), "Copying Typst template");
+        fs::write(&typ_file_path, request.template.as_bytes()).await?;
+
+        let mut images = Vec::with_capacity(ppis.len());
+        for (index, ppi) in ppis.iter().enumerate() {
+            let extension = request.format.extension();
+            let output_path = temp_dir
+                .path()
+                .join(format!("og-image-{index}.{extension}"));
+            self.compile(&request, *ppi, &typ_file_path, &output_path)
+                .await?;
+
+            images.push(fs::read(&output_path).await?);
+        }
+
+        Ok(images)
+    }
+
+    /// Runs the `typst compile` command for the template at `typ_file_path`.
+    async fn compile(
+        &self,
+        request: &RenderRequest,
+        ppi: f32,
+        typ_file_path: &Path,
+        output_path: &Path,
+    ) -> Result<(), OgImageError> {
+        // Run typst compile command with input data
+        info!(ppi, "Running Typst compilation command");
+        let mut command = Command::new(&self.binary_path);
+        command
+            .arg("compile")
+            .arg("--format")
+            .arg(request.format.extension());
+
+        // Pass in the pixel density for raster images
+        if request.format == RenderFormat::Png {
+            command.arg("--ppi").arg(ppi.to_string());
+        }
+
+        // Pass in the data and avatar map as JSON inputs
+        for (key, value) in &request.inputs {
+            command.arg("--input").arg(format!("{key}={value}"));
+        }
+
+        // Pass in the font path if specified
+        if let Some(font_path) = &self.font_path {
+            debug!(font_path = %font_path.display(), "Using custom font path");
+            command.arg("--font-path").arg(font_path);
+            command.arg("--ignore-system-fonts");
+        } else {
+            debug!("Using system font discovery");
+        }
+
+        // Pass input and output file paths
+        command.arg(typ_file_path).arg(output_path);
+
+        // Clear environment variables to avoid leaking sensitive data
+        command.env_clear();
+
+        // Preserve environment variables needed for font discovery
+        if let Ok(path) = std::env::var("PATH") {
+            command.env("PATH", path);
+        }
+        if let Ok(home) = std::env::var("HOME") {
+            command.env("HOME", home);
+        }
+
+        let compilation_start_time = std::time::Instant::now();
+        let output = command.output().await;
+        let output = output.map_err(OgImageError::TypstNotFound)?;
+        let compilation_duration = compilation_start_time.elapsed();
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+            error!(
+                exit_code = ?output.status.code(),
+                stderr = %stderr,
+                stdout = %stdout,
+                duration_ms = compilation_duration.as_millis(),
+                "Typst compilation failed"
+            );
+            return Err(OgImageError::TypstCompilationError {
+                stderr,
+                stdout,
+                exit_code: output.status.code(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for TypstCliRenderer {
+    /// Creates a `TypstCliRenderer` that assumes "typst" is available in PATH
+    /// and uses the default font discovery of Typst.
+    fn default() -> Self {
+        Self::new(PathBuf::from("typst"), None)
+    }
+}
+
+impl Renderer for TypstCliRenderer {
+    fn render(&self, request: RenderRequest) -> RenderFuture<'_> {
+        Box::pin(async move {
+            let ppis = [request.ppi];
+            let mut images = self.render_images(request, &ppis).await?;
+            Ok(images.remove(0))
+        })
+    }
+
+    fn render_densities(
+        &self,
+        request: RenderRequest,
+        ppis: Vec<f32>,
+    ) -> RenderFuture<'_, Vec<Vec<u8>>> {
+        Box::pin(async move { self.render_images(request, &ppis).await })
+    }
+}
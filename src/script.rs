@@ -0,0 +1,99 @@
+//! Script detection for user-provided text, e.g. crate descriptions.
+//!
+//! The template needs to know the direction and language of a text to
+//! align it, to place the ellipsis of truncated text on the correct side,
+//! and to shape and break complex scripts correctly.
+
+use serde::Serialize;
+
+/// The writing direction of a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Direction {
+    Ltr,
+    Rtl,
+}
+
+/// The direction and language of a text, as passed to the template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub(crate) struct Script {
+    pub dir: Direction,
+    /// ISO 639 language code, if the script is mostly used by one language
+    pub lang: Option<&'static str>,
+}
+
+impl Script {
+    const fn new(dir: Direction, lang: Option<&'static str>) -> Self {
+        Self { dir, lang }
+    }
+
+    /// Detects the script of a text from its first letter, similar to how
+    /// the Unicode Bidirectional Algorithm determines the paragraph
+    /// direction.
+    ///
+    /// Returns `None` if the text does not contain any letters, e.g. if it
+    /// only consists of digits and punctuation.
+    pub(crate) fn detect(text: &str) -> Option<Self> {
+        text.chars()
+            .find(|c| c.is_alphabetic())
+            .map(Self::from_char)
+    }
+
+    fn from_char(c: char) -> Self {
+        use Direction::{Ltr, Rtl};
+
+        match c {
+            '\u{0590}'..='\u{05FF}' | '\u{FB1D}'..='\u{FB4F}' => Self::new(Rtl, Some("he")),
+            '\u{0600}'..='\u{06FF}'
+            | '\u{0750}'..='\u{077F}'
+            | '\u{08A0}'..='\u{08FF}'
+            | '\u{FB50}'..='\u{FDFF}'
+            | '\u{FE70}'..='\u{FEFF}' => Self::new(Rtl, Some("ar")),
+            '\u{0700}'..='\u{074F}' => Self::new(Rtl, Some("syr")),
+            '\u{0780}'..='\u{07BF}' => Self::new(Rtl, Some("dv")),
+            '\u{0900}'..='\u{097F}' => Self::new(Ltr, Some("hi")),
+            '\u{0980}'..='\u{09FF}' => Self::new(Ltr, Some("bn")),
+            '\u{0B80}'..='\u{0BFF}' => Self::new(Ltr, Some("ta")),
+            '\u{0E00}'..='\u{0E7F}' => Self::new(Ltr, Some("th")),
+            '\u{3040}'..='\u{30FF}' => Self::new(Ltr, Some("ja")),
+            '\u{1100}'..='\u{11FF}' | '\u{AC00}'..='\u{D7AF}' => Self::new(Ltr, Some("ko")),
+            // Han characters are shared by several languages, and Latin,
+            // Cyrillic etc. are handled fine without a language
+            _ => Self::new(Ltr, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let ltr = |lang| Some(Script::new(Direction::Ltr, lang));
+        let rtl = |lang| Some(Script::new(Direction::Rtl, lang));
+
+        assert_eq!(Script::detect("A fast JSON parser"), ltr(None));
+        assert_eq!(Script::detect("Быстрый парсер"), ltr(None));
+        assert_eq!(Script::detect("مكتبة لتحليل JSON"), rtl(Some("ar")));
+        assert_eq!(Script::detect("ספרייה לניתוח JSON"), rtl(Some("he")));
+        assert_eq!(Script::detect("高速なJSONパーサー"), ltr(None));
+        assert_eq!(Script::detect("これはパーサーです"), ltr(Some("ja")));
+        assert_eq!(Script::detect("ไลบรารี JSON"), ltr(Some("th")));
+        assert_eq!(Script::detect("빠른 파서"), ltr(Some("ko")));
+    }
+
+    #[test]
+    fn test_detect_first_letter() {
+        // Leading digits and punctuation don't determine the direction
+        let script = Script::detect("2024: مكتبة").unwrap();
+        assert_eq!(script.dir, Direction::Rtl);
+
+        // Mixed text uses the direction of the first letter
+        let script = Script::detect("Rust مكتبة").unwrap();
+        assert_eq!(script.dir, Direction::Ltr);
+
+        assert_eq!(Script::detect("1.0 - 2.0"), None);
+        assert_eq!(Script::detect(""), None);
+    }
+}
@@ -0,0 +1,381 @@
+//! Typst templates and the assets they reference.
+
+use crate::OgImageError;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use tokio::fs;
+use tracing::debug;
+
+/// The Typst template bundled with the crate.
+const TEMPLATE: &str = include_str!("../template/og-image.typ");
+
+/// Assets referenced by the bundled Typst template, keyed by their path
+/// relative to the template.
+const ASSETS: &[(&str, &[u8])] = &[
+    (
+        "assets/cargo.png",
+        include_bytes!("../template/assets/cargo.png"),
+    ),
+    (
+        "assets/rust-logo.svg",
+        include_bytes!("../template/assets/rust-logo.svg"),
+    ),
+    (
+        "assets/code-branch.svg",
+        include_bytes!("../template/assets/code-branch.svg"),
+    ),
+    (
+        "assets/download.svg",
+        include_bytes!("../template/assets/download.svg"),
+    ),
+    (
+        "assets/code.svg",
+        include_bytes!("../template/assets/code.svg"),
+    ),
+    (
+        "assets/scale-balanced.svg",
+        include_bytes!("../template/assets/scale-balanced.svg"),
+    ),
+    (
+        "assets/tag.svg",
+        include_bytes!("../template/assets/tag.svg"),
+    ),
+    (
+        "assets/weight-hanging.svg",
+        include_bytes!("../template/assets/weight-hanging.svg"),
+    ),
+];
+
+/// The name of the entry file of a template directory.
+const ENTRY_FILE: &str = "og-image.typ";
+
+/// A Typst template together with the assets it references.
+///
+/// The template receives the same `sys.inputs` as the bundled template,
+/// which are listed in [`Template::ALLOWED_INPUTS`]. Downloaded avatars are
+/// added to the assets as `assets/avatar_<index>.<ext>`.
+///
+/// # Examples
+///
+/// ```
+/// use crates_io_og_image::{OgImageError, OgImageGenerator, Template};
+///
+/// # fn main() -> Result<(), OgImageError> {
+/// let source = r#"
+/// #let data = json(bytes(sys.inputs.data))
+/// #set page(width: 600pt, height: 315pt)
+/// #image("logo.svg", width: 100pt)
+/// = #data.name
+/// "#;
+///
+/// let template = Template::new(source)?.with_asset("logo.svg", b"<svg/>".as_slice())?;
+/// let generator = OgImageGenerator::default().with_template(template);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Template {
+    source: Cow<'static, str>,
+    assets: BTreeMap<String, Cow<'static, [u8]>>,
+}
+
+impl Template {
+    /// The names of the `sys.inputs` that are passed to every template.
+    pub const ALLOWED_INPUTS: &'static [&'static str] = &[
+        "data",
+        "avatar_map",
+        "preset",
+        "theme",
+        "branding",
+        "labels",
+        "fonts",
+    ];
+
+    /// Returns the template bundled with the crate.
+    pub fn bundled() -> Self {
+        let assets = ASSETS
+            .iter()
+            .map(|(path, bytes)| (path.to_string(), Cow::Borrowed(*bytes)))
+            .collect();
+
+        Self {
+            source: Cow::Borrowed(TEMPLATE),
+            assets,
+        }
+    }
+
+    /// Creates a template from the source code of its entry file, without
+    /// any assets.
+    ///
+    /// Returns an error if the source references `sys.inputs` that are not
+    /// listed in [`ALLOWED_INPUTS`](Self::ALLOWED_INPUTS). This check is
+    /// best-effort and only catches typos in input names: templates can
+    /// still access inputs indirectly, but only the allowed inputs are ever
+    /// passed to them.
+    pub fn new(source: impl Into<Cow<'static, str>>) -> Result<Self, OgImageError> {
+        let source = source.into();
+        validate_inputs(&source)?;
+
+        Ok(Self {
+            source,
+            assets: BTreeMap::new(),
+        })
+    }
+
+    /// Adds an asset that can be read by the template at the given path,
+    /// relative to the entry file.
+    ///
+    /// Returns an error if the path is not a relative path inside of the
+    /// template directory.
+    pub fn with_asset(
+        mut self,
+        path: impl Into<String>,
+        bytes: impl Into<Cow<'static, [u8]>>,
+    ) -> Result<Self, OgImageError> {
+        let path = path.into();
+        validate_asset_path(&path)?;
+
+        self.assets.insert(path, bytes.into());
+        Ok(self)
+    }
+
+    /// Loads a template from a directory on disk.
+    ///
+    /// The directory must contain an `og-image.typ` entry file. All other
+    /// files in the directory and its subdirectories are added as assets.
+    pub async fn from_dir(dir: impl AsRef<Path>) -> Result<Self, OgImageError> {
+        let dir = dir.as_ref();
+        debug!(template_dir = %dir.display(), "Loading template directory");
+
+        let entry_path = dir.join(ENTRY_FILE);
+        let source = fs::read_to_string(&entry_path).await;
+        let source = source.map_err(|source| template_read_error(&entry_path, source))?;
+        let mut template = Self::new(source)?;
+
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            let entries = fs::read_dir(&current).await;
+            let mut entries = entries.map_err(|source| template_read_error(&current, source))?;
+
+            while let Some(entry) = entries
+                .next_entry()
+                .await
+                .map_err(|source| template_read_error(&current, source))?
+            {
+                let path = entry.path();
+                let file_type = entry.file_type().await;
+                let file_type = file_type.map_err(|source| template_read_error(&path, source))?;
+
+                if file_type.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+
+                let relative_path = asset_path(dir, &path)?;
+                if relative_path == ENTRY_FILE {
+                    continue;
+                }
+
+                let bytes = fs::read(&path).await;
+                let bytes = bytes.map_err(|source| template_read_error(&path, source))?;
+                template = template.with_asset(relative_path, bytes)?;
+            }
+        }
+
+        debug!(
+            asset_count = template.assets.len(),
+            "Loaded template directory"
+        );
+        Ok(template)
+    }
+
+    /// Returns the source code of the entry file.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the source code of the entry file and the assets, keyed by
+    /// their path relative to the entry file.
+    pub(crate) fn to_parts(&self) -> (Cow<'static, str>, BTreeMap<String, Cow<'static, [u8]>>) {
+        (self.source.clone(), self.assets.clone())
+    }
+}
+
+impl Default for Template {
+    /// Returns the template bundled with the crate.
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
+
+fn template_read_error(path: &Path, source: std::io::Error) -> OgImageError {
+    let path = path.to_path_buf();
+    OgImageError::TemplateReadError { path, source }
+}
+
+/// Converts the path of a file in the template directory into an asset path
+/// with `/` separators.
+fn asset_path(dir: &Path, path: &Path) -> Result<String, OgImageError> {
+    let relative_path = path.strip_prefix(dir).unwrap_or(path);
+
+    let components = relative_path.components().map(|component| {
+        let component = component.as_os_str().to_str();
+        component.ok_or_else(|| {
+            let message = format!("Asset path {} is not valid UTF-8", path.display());
+            OgImageError::InvalidTemplate(message)
+        })
+    });
+
+    Ok(components.collect::<Result<Vec<_>, _>>()?.join("/"))
+}
+
+/// Checks that the asset path stays inside of the template directory.
+fn validate_asset_path(path: &str) -> Result<(), OgImageError> {
+    let is_valid = !path.is_empty()
+        && PathBuf::from(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+
+    if !is_valid {
+        let message = format!("Asset path `{path}` must be relative to the template directory");
+        return Err(OgImageError::InvalidTemplate(message));
+    }
+
+    Ok(())
+}
+
+/// Checks that the template only references the [`Template::ALLOWED_INPUTS`].
+///
+/// This is a best-effort check on the source text. Only inputs that are
+/// accessed by name, i.e. as a field of `sys.inputs` or with a string literal
+/// passed to `sys.inputs.at()`, can be checked, while e.g.
+/// `let i = sys.inputs; i.secret` or `sys.inputs.at(name)` are not detected.
+/// It is not a security boundary: templates only ever receive the allowed
+/// inputs, so unknown inputs are simply missing at render time.
+fn validate_inputs(source: &str) -> Result<(), OgImageError> {
+    const PREFIX: &str = "sys.inputs";
+
+    for (index, _) in source.match_indices(PREFIX) {
+        let rest = &source[index + PREFIX.len()..];
+        let Some(name) = referenced_input(rest) else {
+            continue;
+        };
+
+        if !Template::ALLOWED_INPUTS.contains(&name) {
+            let allowed = Template::ALLOWED_INPUTS.join(", ");
+            let message = format!("Unknown input `{name}`, expected one of: {allowed}");
+            return Err(OgImageError::InvalidTemplate(message));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the name of the input that is accessed by the code following a
+/// `sys.inputs` expression, if any.
+fn referenced_input(rest: &str) -> Option<&str> {
+    let rest = rest.strip_prefix('.')?;
+
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let ident_len = rest.find(|c| !is_ident_char(c)).unwrap_or(rest.len());
+    let (ident, rest) = rest.split_at(ident_len);
+    if ident.is_empty() {
+        return None;
+    }
+
+    match rest.strip_prefix('(') {
+        // Field access, e.g. `sys.inputs.data`
+        None => Some(ident),
+        // Method call with a string literal, e.g. `sys.inputs.at("data")`
+        Some(args) if ident == "at" => {
+            let args = args.trim_start().strip_prefix('"')?;
+            args.split_once('"').map(|(name, _)| name)
+        }
+        // Other dictionary methods, e.g. `sys.inputs.keys()`
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_template_is_valid() {
+        let template = Template::bundled();
+        validate_inputs(template.source()).unwrap();
+        assert!(template.assets.contains_key("assets/cargo.png"));
+    }
+
+    #[test]
+    fn test_new_with_allowed_inputs() {
+        let source = r#"
+            #let data = json(bytes(sys.inputs.data))
+            #let preset = sys.inputs.at("preset", default: none)
+            #let keys = sys.inputs.keys()
+        "#;
+        Template::new(source).unwrap();
+    }
+
+    #[test]
+    fn test_new_with_unknown_field() {
+        let error = Template::new("#sys.inputs.secret").unwrap_err();
+        assert!(error.to_string().contains("`secret`"), "{error}");
+    }
+
+    #[test]
+    fn test_new_with_unknown_at() {
+        let error = Template::new(r#"#sys.inputs.at( "secret" )"#).unwrap_err();
+        assert!(error.to_string().contains("`secret`"), "{error}");
+    }
+
+    #[test]
+    fn test_with_asset_paths() {
+        let template = Template::new("").unwrap();
+        let template = template
+            .with_asset("assets/logo.svg", b"".as_slice())
+            .unwrap();
+        assert!(template.clone().with_asset("", b"".as_slice()).is_err());
+        assert!(
+            template
+                .clone()
+                .with_asset("/etc/passwd", b"".as_slice())
+                .is_err()
+        );
+        assert!(
+            template
+                .clone()
+                .with_asset("../secret.png", b"".as_slice())
+                .is_err()
+        );
+        assert!(
+            template
+                .with_asset("assets/../../secret.png", b"".as_slice())
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_from_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("assets")).unwrap();
+        std::fs::write(dir.path().join(ENTRY_FILE), "#sys.inputs.data").unwrap();
+        std::fs::write(dir.path().join("assets/logo.svg"), "<svg/>").unwrap();
+
+        let template = Template::from_dir(dir.path()).await.unwrap();
+        assert_eq!(template.source(), "#sys.inputs.data");
+
+        let paths = template.assets.keys().collect::<Vec<_>>();
+        assert_eq!(paths, ["assets/logo.svg"]);
+        assert_eq!(template.assets["assets/logo.svg"], b"<svg/>".as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_from_dir_without_entry_file() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let error = Template::from_dir(dir.path()).await.unwrap_err();
+        assert!(matches!(error, OgImageError::TemplateReadError { .. }));
+    }
+}
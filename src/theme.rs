@@ -0,0 +1,343 @@
+//! Color themes of the generated cards.
+
+use crate::OgImageError;
+use serde::Serialize;
+
+/// The minimum contrast ratio of regular text, as required by WCAG 2 level AA.
+const MIN_TEXT_CONTRAST: f64 = 4.5;
+
+/// The minimum contrast ratio of secondary text like metadata labels, as
+/// required by WCAG 2 level AA for large text.
+const MIN_SECONDARY_TEXT_CONTRAST: f64 = 3.;
+
+/// A color of a [`Theme`].
+///
+/// Colors are passed to the template in their original color space, so
+/// that OKLCH colors are rendered without a lossy conversion to sRGB.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "space", rename_all = "lowercase")]
+pub enum Color {
+    /// An sRGB color with 8-bit components.
+    Rgb { r: u8, g: u8, b: u8 },
+    /// A color in the OKLCH color space with a lightness between 0 and 1,
+    /// a chroma between 0 and 0.4, and a hue in degrees.
+    Oklch {
+        lightness: f64,
+        chroma: f64,
+        hue: f64,
+    },
+}
+
+impl Color {
+    /// Creates an sRGB color from its 8-bit components.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::Rgb { r, g, b }
+    }
+
+    /// Creates an OKLCH color from its lightness, chroma and hue in degrees.
+    pub const fn oklch(lightness: f64, chroma: f64, hue: f64) -> Self {
+        Self::Oklch {
+            lightness,
+            chroma,
+            hue,
+        }
+    }
+
+    /// Parses an sRGB color in the `#rrggbb` or `#rgb` hex notation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crates_io_og_image::Color;
+    ///
+    /// assert_eq!(Color::from_hex("#ffa500").unwrap(), Color::rgb(255, 165, 0));
+    /// assert_eq!(Color::from_hex("#fff").unwrap(), Color::rgb(255, 255, 255));
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self, OgImageError> {
+        let invalid = || OgImageError::InvalidColor(hex.to_string());
+
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.is_ascii() {
+            return Err(invalid());
+        }
+
+        let component = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+        match digits.len() {
+            6 => Ok(Self::rgb(
+                component(&digits[0..2])?,
+                component(&digits[2..4])?,
+                component(&digits[4..6])?,
+            )),
+            3 => Ok(Self::rgb(
+                component(&digits[0..1])? * 0x11,
+                component(&digits[1..2])? * 0x11,
+                component(&digits[2..3])? * 0x11,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Returns the relative luminance of the color as defined by WCAG 2.
+    pub fn relative_luminance(&self) -> f64 {
+        let [r, g, b] = self.to_linear_srgb();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// Returns the WCAG 2 contrast ratio between this color and `other`,
+    /// ranging from 1 to 21.
+    pub fn contrast_ratio(&self, other: &Color) -> f64 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Converts the color into linear sRGB components between 0 and 1.
+    ///
+    /// OKLCH colors outside of the sRGB gamut are clipped.
+    fn to_linear_srgb(self) -> [f64; 3] {
+        match self {
+            Self::Rgb { r, g, b } => [r, g, b].map(|component| {
+                let component = f64::from(component) / 255.;
+                if component <= 0.04045 {
+                    component / 12.92
+                } else {
+                    ((component + 0.055) / 1.055).powf(2.4)
+                }
+            }),
+            Self::Oklch {
+                lightness,
+                chroma,
+                hue,
+            } => {
+                let a = chroma * hue.to_radians().cos();
+                let b = chroma * hue.to_radians().sin();
+
+                let l = (lightness + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+                let m = (lightness - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+                let s = (lightness - 0.089_484_177_5 * a - 1.291_485_548 * b).powi(3);
+
+                [
+                    4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s,
+                    -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s,
+                    -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701 * s,
+                ]
+                .map(|component| component.clamp(0., 1.))
+            }
+        }
+    }
+}
+
+/// The colors of the generated card.
+///
+/// # Examples
+///
+/// ```
+/// use crates_io_og_image::{Color, GenerateOptions, Theme};
+///
+/// let theme = Theme {
+///     primary: Color::from_hex("#8f3a00").unwrap(),
+///     ..Theme::light()
+/// };
+/// theme.validate().unwrap();
+///
+/// let options = GenerateOptions::default().with_theme(theme);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Theme {
+    /// Background of the card
+    pub background: Color,
+    /// Background of the header and the bottom border
+    pub header_background: Color,
+    /// Text in the header
+    pub header_text: Color,
+    /// Crate name, metadata values and icons
+    pub primary: Color,
+    /// Description text
+    pub text: Color,
+    /// Authors and metadata labels
+    pub text_light: Color,
+    /// Background of the tags
+    pub tag_background: Color,
+    /// Text of the tags
+    pub tag_text: Color,
+    /// Background of the avatars
+    pub avatar_background: Color,
+    /// Border of the avatars
+    pub avatar_border: Color,
+    /// Color of the Rust logo watermark
+    pub watermark: Color,
+    /// Opacity of the Rust logo watermark between 0 and 1
+    pub watermark_opacity: f64,
+}
+
+impl Theme {
+    /// Returns the light theme with the crates.io green palette.
+    pub fn light() -> Self {
+        let green = Color::oklch(0.36, 0.07, 144.);
+        let white = Color::oklch(1., 0., 0.);
+
+        Self {
+            background: Color::oklch(0.97, 0.0147, 98.),
+            header_background: green,
+            header_text: white,
+            primary: green,
+            text: Color::oklch(0.51, 0.05, 144.),
+            text_light: Color::oklch(0.6, 0.05, 144.),
+            tag_background: green,
+            tag_text: white,
+            avatar_background: white,
+            avatar_border: Color::oklch(0.87, 0.01, 98.),
+            watermark: green,
+            watermark_opacity: 0.2,
+        }
+    }
+
+    /// Returns the dark theme with a light green palette on a dark
+    /// background.
+    pub fn dark() -> Self {
+        let background = Color::oklch(0.22, 0.02, 144.);
+        let green = Color::oklch(0.85, 0.1, 144.);
+
+        Self {
+            background,
+            header_background: Color::oklch(0.32, 0.06, 144.),
+            header_text: Color::oklch(1., 0., 0.),
+            primary: green,
+            text: Color::oklch(0.78, 0.04, 144.),
+            text_light: Color::oklch(0.66, 0.04, 144.),
+            tag_background: green,
+            tag_text: background,
+            avatar_background: Color::oklch(0.3, 0.02, 144.),
+            avatar_border: Color::oklch(0.4, 0.02, 144.),
+            watermark: green,
+            watermark_opacity: 0.1,
+        }
+    }
+
+    /// Checks that all text colors meet the WCAG 2 level AA contrast ratios
+    /// against their background.
+    ///
+    /// Regular text requires a contrast ratio of at least 4.5:1, and the
+    /// small uppercase metadata labels and author names at least 3:1.
+    pub fn validate(&self) -> Result<(), OgImageError> {
+        let checks = [
+            ("text", self.text, self.background, MIN_TEXT_CONTRAST),
+            ("primary", self.primary, self.background, MIN_TEXT_CONTRAST),
+            (
+                "text_light",
+                self.text_light,
+                self.background,
+                MIN_SECONDARY_TEXT_CONTRAST,
+            ),
+            (
+                "header_text",
+                self.header_text,
+                self.header_background,
+                MIN_TEXT_CONTRAST,
+            ),
+            (
+                "tag_text",
+                self.tag_text,
+                self.tag_background,
+                MIN_TEXT_CONTRAST,
+            ),
+        ];
+
+        for (element, foreground, background, required) in checks {
+            let ratio = foreground.contrast_ratio(&background);
+            if ratio < required {
+                return Err(OgImageError::InsufficientContrast {
+                    element,
+                    ratio,
+                    required,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the `theme` input of the template.
+    pub(crate) fn to_input(&self) -> Result<String, OgImageError> {
+        let theme = Self {
+            watermark_opacity: self.watermark_opacity.clamp(0., 1.),
+            ..self.clone()
+        };
+
+        serde_json::to_string(&theme).map_err(OgImageError::JsonSerializationError)
+    }
+}
+
+impl Default for Theme {
+    /// Returns the [light](Self::light) theme.
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(Color::from_hex("#000000").unwrap(), Color::rgb(0, 0, 0));
+        assert_eq!(Color::from_hex("1a2B3c").unwrap(), Color::rgb(26, 43, 60));
+        assert_eq!(Color::from_hex("#abc").unwrap(), Color::rgb(170, 187, 204));
+        assert!(Color::from_hex("#abcd").is_err());
+        assert!(Color::from_hex("#gggggg").is_err());
+        assert!(Color::from_hex("#ä12").is_err());
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let black = Color::rgb(0, 0, 0);
+        let white = Color::rgb(255, 255, 255);
+        assert_eq!(black.contrast_ratio(&white), 21.);
+        assert_eq!(white.contrast_ratio(&black), 21.);
+        assert_eq!(white.contrast_ratio(&white), 1.);
+
+        let oklch_white = Color::oklch(1., 0., 0.);
+        assert!((oklch_white.contrast_ratio(&black) - 21.).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_builtin_themes_are_valid() {
+        Theme::light().validate().unwrap();
+        Theme::dark().validate().unwrap();
+    }
+
+    #[test]
+    fn test_validate_insufficient_contrast() {
+        let theme = Theme {
+            text: Color::from_hex("#eeeeee").unwrap(),
+            ..Theme::light()
+        };
+
+        let error = theme.validate().unwrap_err();
+        assert!(matches!(
+            error,
+            OgImageError::InsufficientContrast {
+                element: "text",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_to_input() {
+        let theme = Theme {
+            primary: Color::rgb(1, 2, 3),
+            watermark_opacity: 1.5,
+            ..Theme::light()
+        };
+
+        let input = theme.to_input().unwrap();
+        let input: serde_json::Value = serde_json::from_str(&input).unwrap();
+        let primary = serde_json::json!({ "space": "rgb", "r": 1, "g": 2, "b": 3 });
+        assert_eq!(input["primary"], primary);
+        assert_eq!(input["background"]["space"], "oklch");
+        assert_eq!(input["background"]["lightness"], 0.97);
+        assert_eq!(input["watermark_opacity"], 1.);
+    }
+}
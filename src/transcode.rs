@@ -0,0 +1,84 @@
+//! Transcoding of rendered PNG images into other raster formats.
+
+use crate::{OgImageError, OutputFormat};
+
+/// Transcodes a rendered PNG image into the given output format.
+///
+/// Returns [`OgImageError::FormatUnavailable`] if the cargo feature
+/// required for the format is not enabled.
+#[cfg(feature = "transcode")]
+pub(crate) async fn transcode(png: Vec<u8>, format: OutputFormat) -> Result<Vec<u8>, OgImageError> {
+    #[cfg(not(feature = "avif"))]
+    if let OutputFormat::Avif { .. } = format {
+        return Err(OgImageError::FormatUnavailable {
+            format,
+            feature: "avif",
+        });
+    }
+
+    tokio::task::spawn_blocking(move || transcode_blocking(&png, format))
+        .await
+        .map_err(OgImageError::RenderTaskError)?
+}
+
+#[cfg(not(feature = "transcode"))]
+pub(crate) async fn transcode(
+    _png: Vec<u8>,
+    format: OutputFormat,
+) -> Result<Vec<u8>, OgImageError> {
+    let feature = match format {
+        OutputFormat::Avif { .. } => "avif",
+        _ => "transcode",
+    };
+
+    Err(OgImageError::FormatUnavailable { format, feature })
+}
+
+#[cfg(feature = "transcode")]
+fn transcode_blocking(png: &[u8], format: OutputFormat) -> Result<Vec<u8>, OgImageError> {
+    use image::codecs::jpeg::JpegEncoder;
+    use image::codecs::webp::WebPEncoder;
+    use image::{DynamicImage, ImageFormat};
+
+    let encoding_error = |err: image::ImageError| OgImageError::ImageEncodingError(err.to_string());
+
+    let image = image::load_from_memory_with_format(png, ImageFormat::Png);
+    let image = image.map_err(encoding_error)?;
+
+    let mut buffer = Vec::new();
+    match format {
+        OutputFormat::WebP { quality: None } => {
+            let encoder = WebPEncoder::new_lossless(&mut buffer);
+            image.write_with_encoder(encoder).map_err(encoding_error)?;
+        }
+        OutputFormat::WebP {
+            quality: Some(quality),
+        } => {
+            // The `image` crate only supports lossless WebP encoding
+            let rgba = image.to_rgba8();
+            let encoder = webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height());
+            buffer.extend_from_slice(&encoder.encode(f32::from(quality.min(100))));
+        }
+        OutputFormat::Jpeg { quality } => {
+            // JPEG does not support transparency
+            let rgb = DynamicImage::from(image.to_rgb8());
+            let encoder = JpegEncoder::new_with_quality(&mut buffer, quality.clamp(1, 100));
+            rgb.write_with_encoder(encoder).map_err(encoding_error)?;
+        }
+        #[cfg(feature = "avif")]
+        OutputFormat::Avif { quality } => {
+            use image::codecs::avif::AvifEncoder;
+
+            let speed = 6;
+            let quality = quality.clamp(1, 100);
+            let encoder = AvifEncoder::new_with_speed_quality(&mut buffer, speed, quality);
+            image.write_with_encoder(encoder).map_err(encoding_error)?;
+        }
+        _ => {
+            let message = format!("{format:?} is not a transcoded format");
+            return Err(OgImageError::ImageEncodingError(message));
+        }
+    }
+
+    Ok(buffer)
+}
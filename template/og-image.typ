@@ -7,19 +7,81 @@
 // COLOR PALETTE
 // =============================================================================
 
-#let colors = (
-    bg: oklch(97%, 0.0147, 98deg),
-    rust-overlay: oklch(36%, 0.07, 144deg, 20%),
-    header-bg: oklch(36%, 0.07, 144deg),
-    header-text: oklch(100%, 0, 0deg),
-    primary: oklch(36%, 0.07, 144deg),
-    text: oklch(51%, 0.05, 144deg),
-    text-light: oklch(60%, 0.05, 144deg),
-    avatar-bg: oklch(100%, 0, 0deg),
-    avatar-border: oklch(87%, 0.01, 98deg),
-    tag-bg: oklch(36%, 0.07, 144deg),
-    tag-text: oklch(100%, 0, 0deg),
-)
+// The theme is passed in by the generator, the palette below is only used
+// if the template is compiled without it
+#let theme = sys.inputs.at("theme", default: none)
+#let theme = if theme != none { json(bytes(theme)) }
+
+// Converts a color of the theme into a Typst color
+// @param color: Object with the 'space' and the components of the color
+// @param alpha: The opacity of the color (default: 100%)
+#let theme-color(color, alpha: 100%) = {
+    if color.space == "oklch" {
+        oklch(color.lightness * 100%, color.chroma, color.hue * 1deg, alpha)
+    } else {
+        rgb(color.r, color.g, color.b, alpha)
+    }
+}
+
+#let colors = if theme == none {
+    (
+        bg: oklch(97%, 0.0147, 98deg),
+        rust-overlay: oklch(36%, 0.07, 144deg, 20%),
+        header-bg: oklch(36%, 0.07, 144deg),
+        header-text: oklch(100%, 0, 0deg),
+        primary: oklch(36%, 0.07, 144deg),
+        text: oklch(51%, 0.05, 144deg),
+        text-light: oklch(60%, 0.05, 144deg),
+        avatar-bg: oklch(100%, 0, 0deg),
+        avatar-border: oklch(87%, 0.01, 98deg),
+        tag-bg: oklch(36%, 0.07, 144deg),
+        tag-text: oklch(100%, 0, 0deg),
+    )
+} else {
+    (
+        bg: theme-color(theme.background),
+        rust-overlay: theme-color(theme.watermark, alpha: theme.watermark_opacity * 100%),
+        header-bg: theme-color(theme.header_background),
+        header-text: theme-color(theme.header_text),
+        primary: theme-color(theme.primary),
+        text: theme-color(theme.text),
+        text-light: theme-color(theme.text_light),
+        avatar-bg: theme-color(theme.avatar_background),
+        avatar-border: theme-color(theme.avatar_border),
+        tag-bg: theme-color(theme.tag_background),
+        tag-text: theme-color(theme.tag_text),
+    )
+}
+
+// =============================================================================
+// LABELS
+// =============================================================================
+
+// The labels are translated by the generator, the English labels below are
+// only used when the template is rendered without them
+#let labels = sys.inputs.at("labels", default: none)
+#let labels = if labels != none { json(bytes(labels)) } else {
+    (
+        releases: "Releases",
+        latest: "Latest",
+        downloads: "Downloads",
+        recent: "Recent",
+        license: "License",
+        sloc: "SLoC",
+        size: "Size",
+        authors_prefix: "by ",
+        authors_separator: ", ",
+        authors_and: " and ",
+        authors_others: (one: " and {n} other", other: " and {n} others"),
+    )
+}
+
+// Selects the plural form of a label and replaces `{n}` with the count
+// @param forms: Object with the 'other' and optional 'one' forms
+#let plural(forms, n) = {
+    let form = if n == 1 and forms.at("one", default: none) != none { forms.one } else { forms.other }
+    form.replace("{n}", str(n))
+}
 
 // =============================================================================
 // LAYOUT CONSTANTS
@@ -32,7 +94,22 @@
 // TEXT TRUNCATION UTILITIES
 // =============================================================================
 // These functions handle text overflow by adding ellipsis when content
-// exceeds specified dimensions
+// exceeds specified dimensions. The ellipsis is appended at the logical end
+// of the text, so it ends up on the left of right-to-left text as long as the
+// text direction is set, see `script-args()`.
+
+// Returns the text arguments for the direction and language of a text
+// @param script: Object with 'dir' ("ltr" or "rtl") and optional 'lang', or none
+#let script-args(script) = {
+    if script == none {
+        return (:)
+    }
+    let args = (dir: if script.dir == "rtl" { rtl } else { ltr })
+    if script.at("lang", default: none) != none {
+        args.insert("lang", script.lang)
+    }
+    args
+}
 
 // Truncates text to fit within a maximum height
 // @param text: The text content to truncate
@@ -128,14 +205,21 @@
 // Complex logic for displaying multiple authors with proper grammar
 
 // Renders an author with optional avatar and name
-// @param author: Object with 'name' and optional 'avatar' properties
+// @param author: Object with 'name' and optional 'avatar' and 'script' properties
 #let render-author(author) = {
     if author.avatar != none {
         h(0.2em)
         box(baseline: 30%, [#render-avatar(author.avatar, size: 1.5em)])
         h(0.2em)
     }
-    author.name
+    // Names in the default direction and language are not wrapped, so that
+    // they are shaped together with the surrounding text
+    let script = author.at("script", default: none)
+    if script == none or (script.dir == "ltr" and script.at("lang", default: none) == none) {
+        author.name
+    } else {
+        text(..script-args(script), author.name)
+    }
 }
 
 // Generates grammatically correct author list text
@@ -144,7 +228,7 @@
         return ""
     }
 
-    let prefix = "by "
+    let prefix = labels.authors_prefix
     let visible = if maxVisible != none {
         calc.min(maxVisible, authors.len())
     } else {
@@ -162,21 +246,17 @@
             authors-text += render-author(authors.at(i))
         } else if i == visible - 1 and visible == authors.len() {
             // Last author and we're showing all authors
-            authors-text += " and " + render-author(authors.at(i))
+            authors-text += labels.authors_and + render-author(authors.at(i))
         } else {
             // Not the last author, or we're truncating
-            authors-text += ", " + render-author(authors.at(i))
+            authors-text += labels.authors_separator + render-author(authors.at(i))
         }
     }
 
     // Add "and X others" suffix if truncated
     if visible < authors.len() {
         let remaining = authors.len() - visible
-        let suffix = " and " + str(remaining) + " other"
-        if remaining > 1 {
-            suffix += "s"
-        }
-        authors-text += suffix
+        authors-text += plural(labels.authors_others, remaining)
     }
 
     return prefix + authors-text
@@ -221,12 +301,12 @@
 // =============================================================================
 // Reusable components for consistent styling
 
-#let render-header = {
+#let render-header(branding) = {
     rect(width: 100%, height: header-height, fill: colors.header-bg, {
         place(left + horizon, dx: 30pt, {
-            box(baseline: 30%, image("assets/cargo.png", width: 35pt))
+            box(baseline: 30%, image(branding.logo, width: 35pt))
             h(10pt)
-            text(size: 22pt, fill: colors.header-text, weight: "semibold")[crates.io]
+            text(size: 22pt, fill: colors.header-text, weight: "semibold", branding.name)
         })
     })
 }
@@ -252,6 +332,37 @@
     )
 }
 
+// Renders a sparkline with the area below the line filled
+// @param values: Array of non-negative numbers, e.g. daily download counts
+// @param width: The width of the sparkline
+// @param height: The height of the sparkline
+#let render-sparkline(values, width: 100pt, height: 30pt) = {
+    // A single value is rendered as a horizontal line
+    let values = if values.len() == 1 { (values.at(0), values.at(0)) } else { values }
+    let max-value = calc.max(..values, 1)
+    let step = width / (values.len() - 1)
+
+    let points = values.enumerate().map(((i, value)) => (
+        i * step,
+        height * (1 - value / max-value),
+    ))
+
+    box(width: width, height: height, {
+        place(polygon(
+            fill: colors.primary.transparentize(80%),
+            stroke: none,
+            (0pt, height),
+            ..points,
+            (width, height),
+        ))
+        place(curve(
+            stroke: (paint: colors.primary, thickness: 1.5pt, join: "round", cap: "round"),
+            curve.move(points.at(0)),
+            ..points.slice(1).map(point => curve.line(point)),
+        ))
+    })
+}
+
 // =============================================================================
 // DATA LOADING
 // =============================================================================
@@ -259,38 +370,114 @@
 
 #let data = json(bytes(sys.inputs.data))
 #let avatar_map = json(bytes(sys.inputs.at("avatar_map", default: "{}")))
+#let preset = json(bytes(sys.inputs.at("preset", default: "{\"width\": 600, \"height\": 315, \"layout\": \"wide\"}")))
+#let branding = json(bytes(sys.inputs.at("branding", default: "{\"name\": \"crates.io\", \"logo\": \"assets/cargo.png\", \"watermark\": \"assets/rust-logo.svg\"}")))
+#let fonts = json(bytes(sys.inputs.at("fonts", default: "[\"Fira Sans\"]")))
+#let fonts = if fonts.len() > 0 { fonts } else { ("Fira Sans",) }
+
+// =============================================================================
+// PRESET LAYOUT
+// =============================================================================
+// Layout parameters that re-flow the content for the aspect ratio of the preset
+
+#let page-width = preset.width * 1pt
+#let page-height = preset.height * 1pt
+#let is-square = preset.layout == "square"
+
+#let content-inset = if is-square { 30pt } else { 35pt }
+#let name-size = if is-square { 30pt } else { 36pt }
+
+// The download counts and the download history are shown in a panel of
+// their own instead of the metadata row, so that they don't take the place
+// of the other metadata. The wide layout places the panel next to the crate
+// name, the square layout places it above the metadata grid.
+#let downloads = data.at("downloads", default: none)
+#let recent-downloads = data.at("recent_downloads", default: none)
+#let download-history = data.at("download_history", default: ())
+#let has-sparkline = download-history.len() > 0
+#let has-download-panel = downloads != none or recent-downloads != none or has-sparkline
+#let download-panel-width = 160pt
+#let sparkline-width = 100pt
+#let sparkline-height = if is-square { 30pt } else { 26pt }
+
+// The crate name and the tags leave room for the download panel in the wide
+// layout
+#let title-width = if has-download-panel and not is-square { 100% - download-panel-width } else { 100% }
+
+// The description fills the vertical space not used by the other elements,
+// which is 60pt for the default Open Graph preset. In the square layout the
+// download panel is placed above the metadata grid and takes some of that
+// space.
+#let description-height = if is-square { page-height - 300pt } else { page-height - 255pt }
+#let description-height = if is-square and has-download-panel { description-height - 40pt } else { description-height }
+
+// The square layout arranges the metadata in a grid of up to two rows instead
+// of a single row. Items that don't fit are dropped instead of being clipped.
+#let render-metadata-items(items) = {
+    if is-square {
+        grid(columns: 3, row-gutter: 10pt, ..items.slice(0, calc.min(items.len(), 6)))
+    } else {
+        layout(size => {
+            let visible = ()
+            let width = 0pt
+            for item in items {
+                width += measure(item).width
+                if width > size.width {
+                    break
+                }
+                visible.push(item)
+            }
+            stack(dir: ltr, visible.join())
+        })
+    }
+}
+
+// Renders the total and recent download counts as a single metadata item,
+// e.g. "1.2M (34K recent)"
+#let render-downloads() = {
+    if downloads != none {
+        let content = if recent-downloads != none {
+            downloads + " (" + recent-downloads + " " + lower(labels.recent) + ")"
+        } else {
+            downloads
+        }
+        render-metadata(labels.downloads, content, "download")
+    } else if recent-downloads != none {
+        render-metadata(labels.recent, recent-downloads, "download")
+    }
+}
 
 // =============================================================================
 // MAIN DOCUMENT
 // =============================================================================
 
-#set page(width: 600pt, height: 315pt, margin: 0pt, fill: colors.bg)
-#set text(font: "Fira Sans", fill: colors.text)
+#set page(width: page-width, height: page-height, margin: 0pt, fill: colors.bg)
+#set text(font: fonts, fill: colors.text)
 
-// Header with crates.io branding
-#render-header
+// Header with the registry branding
+#render-header(branding)
 
 // Bottom border accent
 #place(bottom,
     rect(width: 100%, height: footer-height, fill: colors.header-bg)
 )
 
-// Rust logo overlay (20% opacity watermark)
+// Watermark overlay, the Rust logo by default
 #place(bottom + right, dx: 200pt, dy: 100pt,
-    colored-image("assets/rust-logo.svg", colors.rust-overlay, width: 300pt)
+    colored-image(branding.watermark, colors.rust-overlay, width: 300pt)
 )
 
 // Main content area
 #place(
     left + top,
     dy: 60pt,
-    block(height: 100% - header-height - footer-height, inset: 35pt, clip: true, {
+    block(width: 100%, height: 100% - header-height - footer-height, inset: content-inset, clip: true, {
         // Crate name
-        block(text(size: 36pt, weight: "semibold", fill: colors.primary, truncate_to_width(data.name)))
+        block(width: title-width, text(size: name-size, weight: "semibold", fill: colors.primary, truncate_to_width(data.name)))
 
         // Tags
         if data.at("tags", default: ()).len() > 0 {
-            block(
+            block(width: title-width,
                 for (i, tag) in data.tags.enumerate() {
                     if i > 0 {
                         h(3pt)
@@ -302,7 +489,8 @@
 
         // Description
         if data.at("description", default: none) != none {
-            block(text(size: 14pt, weight: "regular", truncate_to_height(data.description, maxHeight: 60pt)))
+            let args = script-args(data.at("description_script", default: none))
+            block(text(size: 14pt, weight: "regular", ..args, truncate_to_height(data.description, maxHeight: description-height)))
         }
 
         // Authors
@@ -316,27 +504,40 @@
                         avatar = "assets/" + avatar_path
                     }
                 }
-                (name: author.name, avatar: avatar)
+                (name: author.name, avatar: avatar, script: author.at("script", default: none))
             })
             block(render-authors-list(authors-with-avatars))
         }
 
-        place(bottom + left, float: true,
-            stack(dir: ltr, {
-                if data.at("releases", default: none) != none {
-                    render-metadata("Releases", data.releases, "tag")
-                }
-                render-metadata("Latest", truncate_to_width("v" + data.version, maxWidth: 80pt), "code-branch")
-                if data.at("license", default: none) != none {
-                    render-metadata("License", truncate_to_width(data.license, maxWidth: 100pt), "scale-balanced")
-                }
-                if data.at("lines_of_code", default: none) != none {
-                    render-metadata("SLoC", data.lines_of_code, "code")
-                }
-                if data.at("crate_size", default: none) != none {
-                    render-metadata("Size", data.crate_size, "weight-hanging")
-                }
-            })
-        )
+        // Metadata
+        let metadata-items = ()
+        if data.at("releases", default: none) != none {
+            metadata-items.push(render-metadata(labels.releases, data.releases, "tag"))
+        }
+        metadata-items.push(render-metadata(labels.latest, truncate_to_width("v" + data.version, maxWidth: 80pt), "code-branch"))
+        if data.at("license", default: none) != none {
+            metadata-items.push(render-metadata(labels.license, truncate_to_width(data.license, maxWidth: 100pt), "scale-balanced"))
+        }
+        if data.at("lines_of_code", default: none) != none {
+            metadata-items.push(render-metadata(labels.sloc, data.lines_of_code, "code"))
+        }
+        if data.at("crate_size", default: none) != none {
+            metadata-items.push(render-metadata(labels.size, data.crate_size, "weight-hanging"))
+        }
+
+        // Download counts and history
+        if has-download-panel {
+            let sparkline = if has-sparkline {
+                render-sparkline(download-history, width: sparkline-width, height: sparkline-height)
+            }
+            if is-square {
+                place(bottom + left, dy: -75pt, render-downloads())
+                place(bottom + right, dy: -75pt, sparkline)
+            } else {
+                place(top + right, align(left, stack(spacing: 8pt, render-downloads(), sparkline)))
+            }
+        }
+
+        place(bottom + left, float: true, render-metadata-items(metadata-items))
     })
 )